@@ -1,17 +1,39 @@
 /// Application state and main loop for gli-editor
-use crate::core::backup::BackupManager;
-use crate::core::file_reader::FileContext;
-use crate::error::Result;
-use crate::models::line::LineRange;
+use crate::ui::backups::BackupsWidget;
+use crate::ui::clipboard;
+use crate::ui::conflict::ConflictWidget;
+use crate::ui::report::ReportWidget;
+use crate::ui::theme::Theme;
 use crate::ui::viewer::ViewerWidget;
 use crossterm::{
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use gli_editor::core::backup::{BackupEntry, BackupManager};
+use gli_editor::core::config::Config;
+use gli_editor::core::diff::{self, DiffKind, DiffLine};
+use gli_editor::core::duplicates::DuplicateIndex;
+use gli_editor::core::file_reader::FileContext;
+use gli_editor::core::filter::FieldFilter;
+use gli_editor::core::git::GitRepository;
+use gli_editor::core::highlight::Token;
+use gli_editor::core::history::{Change, EditOperation, History};
+use gli_editor::core::preview::{self, PreviewKey, PreviewLoader, Source};
+use gli_editor::core::report::{self, Finding};
+use gli_editor::core::search::{Search, SearchQuery};
+use gli_editor::core::stale::{self, StaleReport};
+use gli_editor::core::watcher::FileWatcher;
+use gli_editor::error::{GliError, Result};
+use gli_editor::models::fingerprint::Fingerprint;
+use gli_editor::models::line::{Line, LineRange};
+use gli_editor::models::pattern::PatternType;
 use ratatui::{backend::CrosstermBackend, Terminal};
+use std::collections::{HashMap, HashSet};
 use std::io;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::time::SystemTime;
 use tui_textarea::TextArea;
 
 /// View mode state
@@ -22,21 +44,202 @@ pub struct ViewState {
     pub current_line: usize,
     pub preview_enabled: bool,
     pub preview_content: Option<PreviewContent>,
+    /// Lines shown above and below the target line in the preview
+    pub preview_context: usize,
+    /// Width of the preview pane in percent of the screen
+    pub preview_width: u16,
+    /// Active search within the preview source
+    pub preview_search: Option<Search>,
+    /// Report findings by fingerprint, used to mark the secret in the preview
+    findings: HashMap<Fingerprint, Finding>,
+    /// Show the secret in the preview instead of masking it (reset for every entry)
+    pub secret_revealed: bool,
+    /// Loads preview sources in the background
+    preview_loader: PreviewLoader,
+    /// Git repository containing the file (None outside a repository)
+    pub repository: Option<GitRepository>,
+    /// Directory fingerprint paths are relative to
+    pub root: PathBuf,
+    /// Number of content rows available on screen
+    pub viewport_height: usize,
+    /// Active search, used for highlighting and n/N navigation
+    pub search: Option<Search>,
+    /// Active field filter
+    pub filter: Option<FieldFilter>,
+    /// Line numbers matching the filter, in file order (None when unfiltered)
+    filtered_lines: Option<Vec<usize>>,
+    /// Result of the last stale-entry validation (None until validated)
+    pub stale_report: Option<StaleReport>,
+    /// Line where the visual-line selection started (None outside visual mode)
+    pub visual_anchor: Option<usize>,
+    /// Lines kept visible above and below the cursor when scrolling
+    pub scroll_margin: usize,
+    /// Colors of the file view
+    pub theme: Theme,
 }
 
+/// Default number of lines kept visible above and below the cursor when scrolling
+const SCROLL_MARGIN: usize = 3;
+
+/// Default number of lines shown above and below the target line in the preview
+const PREVIEW_CONTEXT: usize = 10;
+
+/// Step for changing the preview context with `+`/`-`
+const PREVIEW_CONTEXT_STEP: usize = 5;
+
+/// Default, minimum and maximum width of the preview pane (percent), and the step for
+/// `<`/`>`
+const PREVIEW_WIDTH: u16 = 50;
+const PREVIEW_WIDTH_MIN: u16 = 20;
+const PREVIEW_WIDTH_MAX: u16 = 80;
+const PREVIEW_WIDTH_STEP: u16 = 10;
+
 /// Preview content for the selected line
 #[derive(Debug, Clone)]
 pub struct PreviewContent {
     pub file_path: String,
     pub target_line: usize,
-    pub lines: Vec<String>,
-    pub start_line: usize,
+    /// All lines of the source file (empty while loading)
+    pub source: Source,
+    /// Line the pane is scrolled to, marked while the pane is focused (1-based)
+    pub focus_line: usize,
+    /// First line shown in the pane (1-based)
+    pub top_line: usize,
+    /// The source is still being loaded in the background
+    pub loading: bool,
+    /// Why the source could not be loaded
+    pub error: Option<String>,
+    /// Byte ranges of the secret reported for this fingerprint, per line
+    pub match_ranges: Vec<(usize, Range<usize>)>,
+    /// Byte ranges masked until revealed, per line: the reported secret, or a guess on
+    /// the target line
+    pub mask_ranges: Vec<(usize, Range<usize>)>,
+}
+
+impl PreviewContent {
+    /// Placeholder shown while the source is loaded
+    fn loading(key: &PreviewKey, target_line: usize) -> Self {
+        Self {
+            file_path: key.label(),
+            target_line,
+            source: Source::default(),
+            focus_line: target_line,
+            top_line: 1,
+            loading: true,
+            error: None,
+            match_ranges: Vec::new(),
+            mask_ranges: Vec::new(),
+        }
+    }
+
+    /// Reason the source could not be loaded, shown in place of the lines
+    fn failed(key: &PreviewKey, target_line: usize, error: GliError) -> Self {
+        let reason = match error {
+            GliError::FileNotFound(reason) => reason,
+            other => other.to_string(),
+        };
+        Self {
+            error: Some(reason),
+            loading: false,
+            ..Self::loading(key, target_line)
+        }
+    }
+
+    /// A loaded source, scrolled to the top (None if the source is empty)
+    fn from_source(key: &PreviewKey, target_line: usize, source: Source) -> Option<Self> {
+        if source.lines.is_empty() {
+            return None;
+        }
+
+        // Clamp target_line to file bounds
+        let target_line = target_line.clamp(1, source.lines.len());
+
+        Some(Self {
+            file_path: key.label(),
+            target_line,
+            source,
+            focus_line: target_line,
+            top_line: 1,
+            loading: false,
+            error: None,
+            match_ranges: Vec::new(),
+            mask_ranges: Vec::new(),
+        })
+    }
+
+    /// Reported secret ranges on a line (1-based)
+    pub fn line_match_ranges(&self, line_number: usize) -> Vec<Range<usize>> {
+        Self::ranges_on(&self.match_ranges, line_number)
+    }
+
+    /// Masked ranges on a line (1-based)
+    pub fn line_mask_ranges(&self, line_number: usize) -> Vec<Range<usize>> {
+        Self::ranges_on(&self.mask_ranges, line_number)
+    }
+
+    fn ranges_on(ranges: &[(usize, Range<usize>)], line_number: usize) -> Vec<Range<usize>> {
+        ranges
+            .iter()
+            .filter(|(line, _)| *line == line_number)
+            .map(|(_, range)| range.clone())
+            .collect()
+    }
+
+    /// Lines shown in the pane with their (1-based) line numbers and syntax tokens:
+    /// `2 * context + 1` lines starting at `top_line`
+    pub fn visible_lines(
+        &self,
+        context: usize,
+    ) -> impl Iterator<Item = (usize, &String, &[Token])> {
+        self.source
+            .lines
+            .iter()
+            .enumerate()
+            .skip(self.top_line - 1)
+            .take(2 * context + 1)
+            .map(|(idx, line)| (idx + 1, line, self.source.line_tokens(idx)))
+    }
+
+    /// Next line matching `search` after (or at, if `inclusive`) `from`, wrapping around
+    fn find_match(
+        &self,
+        search: &Search,
+        from: usize,
+        forward: bool,
+        inclusive: bool,
+    ) -> Option<usize> {
+        let total = self.source.lines.len();
+        if total == 0 {
+            return None;
+        }
+
+        let from = from.clamp(1, total) - 1;
+        let offsets = if inclusive { 0..total } else { 1..total + 1 };
+        offsets
+            .map(|offset| {
+                let offset = offset % total;
+                if forward {
+                    (from + offset) % total
+                } else {
+                    (from + total - offset) % total
+                }
+            })
+            .find(|&idx| search.is_match(&self.source.lines[idx]))
+            .map(|idx| idx + 1)
+    }
 }
 
 impl ViewState {
-    pub fn new(file_context: FileContext, start_line: usize, end_line: usize) -> Result<Self> {
-        let lines = file_context.get_range(start_line, end_line)?;
-        let visible_range = LineRange::new(start_line, end_line, lines);
+    /// `config.root` overrides the directory fingerprint paths are resolved against (see
+    /// [`stale::scan_root`]); the view and preview settings and colors also come from
+    /// `config`.
+    pub fn new(
+        file_context: FileContext,
+        start_line: usize,
+        end_line: usize,
+        config: &Config,
+    ) -> Result<Self> {
+        let visible_range = LineRange::new(start_line, end_line);
 
         // Set current_line to 1 for non-empty files, 0 for empty files
         let current_line = if start_line == 0 && end_line == 0 {
@@ -45,179 +248,341 @@ impl ViewState {
             start_line.max(1) // Ensure at least line 1 for non-empty files
         };
 
-        Ok(Self {
+        let repository = GitRepository::discover(&file_context.file_path);
+        let root = config
+            .root
+            .clone()
+            .unwrap_or_else(|| stale::scan_root(&file_context.file_path, repository.as_ref()));
+        let preview_width = config
+            .preview
+            .width
+            .map_or(PREVIEW_WIDTH, |width| width.clamp(PREVIEW_WIDTH_MIN, PREVIEW_WIDTH_MAX));
+
+        let view_state = Self {
             file_context,
             visible_range,
             scroll_offset: 0,
             current_line,
             preview_enabled: true,
             preview_content: None,
-        })
+            preview_context: config.preview.context.unwrap_or(PREVIEW_CONTEXT),
+            preview_width,
+            preview_search: None,
+            findings: HashMap::new(),
+            secret_revealed: false,
+            preview_loader: PreviewLoader::new(repository.clone(), root.clone()),
+            repository,
+            root,
+            viewport_height: (end_line + 1).saturating_sub(start_line),
+            search: None,
+            filter: None,
+            filtered_lines: None,
+            stale_report: None,
+            visual_anchor: None,
+            scroll_margin: config.view.scroll_margin.unwrap_or(SCROLL_MARGIN),
+            theme: Theme::from_config(&config.colors)?,
+        };
+        view_state.check_rows(start_line, end_line)?;
+        Ok(view_state)
     }
 
-    /// Update preview content for the current line
-    pub fn update_preview(&mut self) {
-        self.preview_content = None;
-
-        if !self.preview_enabled {
-            return;
+    /// Number of displayed rows (matching lines when filtered, all lines otherwise)
+    pub fn total_rows(&self) -> usize {
+        match self.filtered_lines {
+            Some(ref lines) => lines.len(),
+            None => self.file_context.total_lines,
         }
+    }
 
-        // Get current line
-        if let Some(line) = self.file_context.get_line(self.current_line) {
-            // Extract file path and line number from fingerprint
-            if let crate::models::pattern::PatternType::Fingerprint {
-                commit_hash,
-                file_path,
-                line_number,
-                ..
-            } = &line.pattern_type
-            {
-                // Try to read the target file
-                let result = if let Some(hash) = commit_hash {
-                    // Read from specific git commit
-                    Self::read_preview_from_git(hash, file_path, *line_number)
-                } else {
-                    // Read from current filesystem
-                    Self::read_preview_file(file_path, *line_number)
-                };
+    /// Line number displayed at a 1-based row
+    pub fn line_at_row(&self, row: usize) -> usize {
+        match self.filtered_lines {
+            Some(ref lines) => lines.get(row.wrapping_sub(1)).copied().unwrap_or(0),
+            None => row,
+        }
+    }
 
-                match result {
-                    Ok(content) => {
-                        self.preview_content = Some(content);
-                    }
-                    Err(_e) => {
-                        // Silently fail - preview just won't be shown
-                    }
-                }
-            }
+    /// Row displaying `line_number`, or None if it is hidden by the filter
+    pub fn row_of_line(&self, line_number: usize) -> Option<usize> {
+        match self.filtered_lines {
+            Some(ref lines) => lines.binary_search(&line_number).ok().map(|idx| idx + 1),
+            None => (line_number >= 1 && line_number <= self.file_context.total_lines)
+                .then_some(line_number),
         }
     }
 
-    /// Read preview content from a specific git commit
-    fn read_preview_from_git(commit_hash: &str, file_path: &str, target_line: u32) -> Result<PreviewContent> {
-        use std::process::Command;
+    /// Row of the first displayed line at or after `line_number`, clamped to the last row
+    ///
+    /// Returns 0 if nothing is displayed.
+    pub fn nearest_row(&self, line_number: usize) -> usize {
+        let total_rows = self.total_rows();
+        let row = match self.filtered_lines {
+            Some(ref lines) => lines.partition_point(|&l| l < line_number) + 1,
+            None => line_number.max(1),
+        };
+        row.min(total_rows)
+    }
 
-        let target_line = target_line as usize;
-        let context = 10; // Show ±10 lines around target
+    /// Row of the cursor line
+    pub fn current_row(&self) -> usize {
+        self.nearest_row(self.current_line)
+    }
 
-        // Use git show to get file content at specific commit
-        let output = Command::new("git")
-            .args(["show", &format!("{}:{}", commit_hash, file_path)])
-            .output()
-            .map_err(|e| {
-                crate::error::GliError::FileNotFound(format!("Failed to execute git: {}", e))
-            })?;
+    /// First and last row (inclusive) of the visual-line selection
+    pub fn selected_rows(&self) -> Option<(usize, usize)> {
+        let anchor_row = self.row_of_line(self.visual_anchor?)?;
+        let current_row = self.current_row();
+        Some((anchor_row.min(current_row), anchor_row.max(current_row)))
+    }
 
-        if !output.status.success() {
-            return Err(crate::error::GliError::FileNotFound(format!(
-                "Git command failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            )));
+    /// Line numbers of the visual-line selection, in ascending order
+    pub fn selected_lines(&self) -> Vec<usize> {
+        match self.selected_rows() {
+            Some((start, end)) => (start..=end).map(|row| self.line_at_row(row)).collect(),
+            None => Vec::new(),
         }
+    }
 
-        // Parse output as lines
-        let content = String::from_utf8_lossy(&output.stdout);
-        let all_lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    /// Check that a range of rows (1-based, inclusive) can be displayed
+    pub fn check_rows(&self, start: usize, end: usize) -> Result<()> {
+        if start == 0 && end == 0 {
+            return Ok(());
+        }
 
-        if all_lines.is_empty() {
-            return Err(crate::error::GliError::FileNotFound(
-                "File is empty in commit".to_string(),
-            ));
+        let total_rows = self.total_rows();
+        if start == 0 || end > total_rows || start > end {
+            return Err(GliError::LineOutOfBounds(end, total_rows));
         }
 
-        // Clamp target_line to file bounds
-        let target_line = target_line.min(all_lines.len());
+        Ok(())
+    }
 
-        let start_line = target_line.saturating_sub(context).max(1);
-        let end_line = (target_line + context).min(all_lines.len());
+    /// Visible rows with their line numbers, read straight from the buffer
+    pub fn visible_lines(&self) -> impl Iterator<Item = (usize, usize, &Line)> {
+        let range = self.visible_range;
+        (range.start_line.max(1)..=range.end_line).filter_map(move |row| {
+            let line_number = self.line_at_row(row);
+            self.file_context
+                .get_line(line_number)
+                .map(|line| (row, line_number, line))
+        })
+    }
 
-        // Ensure start_line is valid for slicing
-        let start_idx = (start_line - 1).min(all_lines.len().saturating_sub(1));
-        let end_idx = end_line.min(all_lines.len());
+    /// Set (or clear) the field filter
+    ///
+    /// The caller is responsible for refreshing the visible range afterwards.
+    pub fn set_filter(&mut self, filter: Option<FieldFilter>) {
+        self.filter = filter;
+        self.apply_filter();
+    }
 
-        let lines = if start_idx < end_idx {
-            all_lines[start_idx..end_idx].to_vec()
-        } else {
-            vec![]
-        };
+    /// Recompute the lines matching the filter
+    pub fn apply_filter(&mut self) {
+        self.filtered_lines = self.filter.as_ref().map(|filter| {
+            self.file_context
+                .numbered_lines()
+                .filter(|(_, line)| filter.matches(line.pattern_type()))
+                .map(|(line_number, _)| line_number)
+                .collect()
+        });
+    }
 
-        Ok(PreviewContent {
-            file_path: format!("{}@{}", file_path, &commit_hash[..7]), // Show short hash in preview
-            target_line,
-            lines,
-            start_line,
-        })
+    /// Find the next displayed row whose line satisfies `predicate`, wrapping around
+    ///
+    /// Starts at `from_row` itself when `include_start` is true, otherwise at the row
+    /// after (or before, when searching backwards) it.
+    pub fn find_row(
+        &self,
+        from_row: usize,
+        forward: bool,
+        include_start: bool,
+        predicate: impl Fn(&Line) -> bool,
+    ) -> Option<usize> {
+        let total = self.total_rows();
+        if total == 0 {
+            return None;
+        }
+
+        // Work with 0-based indices; from_row may be 0 for an empty cursor
+        let start_idx = from_row.clamp(1, total) - 1;
+        let first_offset = if include_start { 0 } else { 1 };
+
+        (first_offset..=total)
+            .map(|offset| {
+                if forward {
+                    (start_idx + offset) % total
+                } else {
+                    (start_idx + total * 2 - offset) % total
+                }
+            })
+            .find(|&idx| {
+                self.file_context
+                    .get_line(self.line_at_row(idx + 1))
+                    .is_some_and(&predicate)
+            })
+            .map(|idx| idx + 1)
     }
 
-    /// Read preview content from target file
-    fn read_preview_file(file_path: &str, target_line: u32) -> Result<PreviewContent> {
-        use std::fs::File;
-        use std::io::{BufRead, BufReader};
-        use std::path::Path;
+    /// Number of displayed rows satisfying `predicate`, and the 1-based position of
+    /// the cursor line among them (0 if the cursor line does not satisfy it)
+    pub fn count_rows(&self, predicate: impl Fn(&Line) -> bool) -> (usize, usize) {
+        let mut total = 0;
+        let mut position = 0;
+        for row in 1..=self.total_rows() {
+            let line_number = self.line_at_row(row);
+            if self.file_context.get_line(line_number).is_some_and(&predicate) {
+                total += 1;
+                if line_number == self.current_line {
+                    position = total;
+                }
+            }
+        }
+        (position, total)
+    }
 
-        let target_line = target_line as usize;
-        let context = 10; // Show ±10 lines around target
+    /// Update preview content for the current line
+    ///
+    /// Cached sources are shown right away; others show a loading placeholder until
+    /// [`ViewState::poll_preview`] receives them from the background loader.
+    pub fn update_preview(&mut self) {
+        self.preview_content = None;
+        self.secret_revealed = false;
 
-        // Handle relative paths by resolving them
-        let path = Path::new(file_path);
-        let absolute_path = if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            // Resolve relative path from current directory
-            std::env::current_dir()
-                .map(|cwd| cwd.join(file_path))
-                .unwrap_or_else(|_| path.to_path_buf())
+        let fingerprint = self
+            .file_context
+            .get_line(self.current_line)
+            .and_then(|line| line.pattern_type().fingerprint())
+            .filter(|_| self.preview_enabled);
+
+        let Some(fingerprint) = fingerprint else {
+            self.preview_loader.cancel();
+            return;
         };
 
-        // Check if file exists before trying to open it
-        if !absolute_path.exists() {
-            return Err(crate::error::GliError::FileNotFound(format!(
-                "Preview file not found: {} (resolved to: {})",
-                file_path,
-                absolute_path.display()
-            )));
+        let key = PreviewKey::from(fingerprint);
+        let target_line = fingerprint.line_number as usize;
+        match self.preview_loader.request(&key) {
+            Some(source) => self.show_preview_source(&key, target_line, source),
+            None => self.preview_content = Some(PreviewContent::loading(&key, target_line)),
         }
+    }
 
-        let file = File::open(&absolute_path).map_err(|e| {
-            crate::error::GliError::IoError(e)
-        })?;
+    /// Show a loaded source, scrolled to the target line
+    ///
+    /// If the report has a finding for the current fingerprint, its secret is marked and
+    /// masked; otherwise the value on the target line is masked.
+    fn show_preview_source(&mut self, key: &PreviewKey, target_line: usize, source: Source) {
+        let finding = self
+            .file_context
+            .get_line(self.current_line)
+            .and_then(|line| line.pattern_type().fingerprint())
+            .and_then(|fingerprint| self.findings.get(fingerprint));
+        let match_ranges = finding
+            .map(|finding| finding.match_ranges(&source.lines))
+            .unwrap_or_default();
+        let mut mask_ranges = finding
+            .map(|finding| finding.secret_ranges(&source.lines))
+            .unwrap_or_default();
+
+        self.preview_content = PreviewContent::from_source(key, target_line, source);
+        if let Some(ref mut preview) = self.preview_content {
+            if mask_ranges.is_empty() {
+                let target_line = preview.target_line;
+                mask_ranges = preview::guess_secret_range(&preview.source.lines[target_line - 1])
+                    .map(|range| vec![(target_line, range)])
+                    .unwrap_or_default();
+            }
+            preview.match_ranges = match_ranges;
+            preview.mask_ranges = mask_ranges;
+        }
+        self.scroll_preview_to_target();
+    }
 
-        let reader = BufReader::new(file);
-        let all_lines: Vec<String> = reader
-            .lines()
-            .filter_map(|l| l.ok())
+    /// Use the findings of a gitleaks report to mark secrets in the preview
+    pub fn set_report_findings(&mut self, findings: &[Finding]) {
+        self.findings = findings
+            .iter()
+            .filter_map(|finding| Some((finding.fingerprint.parse().ok()?, finding.clone())))
             .collect();
+    }
 
-        // Validate target_line is within file bounds
-        if all_lines.is_empty() {
-            return Err(crate::error::GliError::FileNotFound(
-                "Preview file is empty".to_string(),
-            ));
+    /// Show the preview once the background loader has finished
+    ///
+    /// Sources that cannot be read (missing file or commit) show the reason instead.
+    pub fn poll_preview(&mut self) {
+        let Some((key, result)) = self.preview_loader.poll() else {
+            return;
+        };
+        let Some(ref preview) = self.preview_content else {
+            return;
+        };
+
+        let target_line = preview.target_line;
+        match result {
+            Ok(source) => self.show_preview_source(&key, target_line, source),
+            Err(e) => self.preview_content = Some(PreviewContent::failed(&key, target_line, e)),
         }
+    }
 
-        // Clamp target_line to file bounds
-        let target_line = target_line.min(all_lines.len());
+    /// Rows between the top of the preview pane and the line it is scrolled to
+    ///
+    /// The line is shown `preview_context` lines below the top, or centred if the pane
+    /// is smaller than that.
+    fn preview_anchor(&self) -> usize {
+        self.preview_context.min(self.viewport_height / 2)
+    }
+
+    /// Line the preview is scrolled to (the target line until the pane is scrolled)
+    pub fn preview_focus_line(&self) -> usize {
+        self.preview_content.as_ref().map_or(0, |preview| preview.focus_line)
+    }
 
-        let start_line = target_line.saturating_sub(context).max(1);
-        let end_line = (target_line + context).min(all_lines.len());
+    /// Scroll the preview so that `line` is shown at the anchor row
+    pub fn scroll_preview_to(&mut self, line: usize) {
+        let anchor = self.preview_anchor();
+        if let Some(ref mut preview) = self.preview_content {
+            preview.focus_line = line.clamp(1, preview.source.lines.len().max(1));
+            preview.top_line = preview.focus_line.saturating_sub(anchor).max(1);
+        }
+    }
+
+    /// Scroll the preview back to the target line
+    pub fn scroll_preview_to_target(&mut self) {
+        if let Some(target_line) = self.preview_content.as_ref().map(|p| p.target_line) {
+            self.scroll_preview_to(target_line);
+        }
+    }
 
-        // Ensure start_line is valid for slicing
-        let start_idx = (start_line - 1).min(all_lines.len().saturating_sub(1));
-        let end_idx = end_line.min(all_lines.len());
+    /// Scroll the preview by `delta` lines
+    pub fn scroll_preview(&mut self, delta: isize) {
+        let focus_line = self.preview_focus_line();
+        self.scroll_preview_to(focus_line.saturating_add_signed(delta));
+    }
 
-        let lines = if start_idx < end_idx {
-            all_lines[start_idx..end_idx].to_vec()
+    /// Change the preview context, keeping the focused line in place
+    pub fn resize_preview_context(&mut self, grow: bool) {
+        let focus_line = self.preview_focus_line();
+        self.preview_context = if grow {
+            self.preview_context + PREVIEW_CONTEXT_STEP
         } else {
-            vec![]
+            self.preview_context.saturating_sub(PREVIEW_CONTEXT_STEP)
         };
+        self.scroll_preview_to(focus_line);
+    }
 
-        Ok(PreviewContent {
-            file_path: file_path.to_string(),
-            target_line,
-            lines,
-            start_line,
-        })
+    /// Move the divider between the file and the preview pane left or right
+    pub fn resize_preview_width(&mut self, grow: bool) {
+        self.preview_width = if grow {
+            self.preview_width + PREVIEW_WIDTH_STEP
+        } else {
+            self.preview_width.saturating_sub(PREVIEW_WIDTH_STEP)
+        }
+        .clamp(PREVIEW_WIDTH_MIN, PREVIEW_WIDTH_MAX);
+    }
+
+    /// Whether the preview pane is on screen
+    pub fn preview_shown(&self) -> bool {
+        self.preview_enabled && self.preview_content.is_some()
     }
 }
 
@@ -226,6 +591,10 @@ pub struct EditState {
     pub textarea: TextArea<'static>,
     pub original_line: usize,
     pub original_content: String,
+    /// Line opened with `o`/`O`; removed again if the edit is cancelled
+    pub inserted: bool,
+    /// Cursor line before the line was opened
+    pub return_line: usize,
 }
 
 impl EditState {
@@ -238,12 +607,24 @@ impl EditState {
             textarea,
             original_line: line_number,
             original_content: content,
+            inserted: false,
+            return_line: line_number,
+        }
+    }
+
+    /// Edit state for a freshly opened empty line
+    pub fn new_line(line_number: usize, return_line: usize) -> Self {
+        Self {
+            inserted: true,
+            return_line,
+            ..Self::new(line_number, String::new())
         }
     }
 
     pub fn has_changes(&self) -> bool {
+        // A new line is written even if left empty
         let current = self.textarea.lines().join("");
-        current != self.original_content
+        self.inserted || current != self.original_content
     }
 
     pub fn get_content(&self) -> String {
@@ -251,11 +632,257 @@ impl EditState {
     }
 }
 
+/// Search prompt state
+pub struct SearchState {
+    pub textarea: TextArea<'static>,
+    pub case_insensitive: bool,
+    pub regex: bool,
+    /// Searching the preview source instead of the file
+    pub in_preview: bool,
+    /// Line the cursor (or the preview) was on when the prompt was opened
+    pub origin_line: usize,
+    /// Search that was active before the prompt was opened (restored on cancel)
+    previous_search: Option<Search>,
+    /// Compile error for the current pattern
+    pub error: Option<String>,
+}
+
+impl SearchState {
+    pub fn new(origin_line: usize, previous_search: Option<Search>) -> Self {
+        // Reuse options from the previous search, defaulting to case-insensitive
+        let (case_insensitive, regex) = previous_search
+            .as_ref()
+            .map(|s| (s.query.case_insensitive, s.query.regex))
+            .unwrap_or((true, false));
+
+        Self {
+            textarea: TextArea::default(),
+            case_insensitive,
+            regex,
+            in_preview: false,
+            origin_line,
+            previous_search,
+            error: None,
+        }
+    }
+
+    /// Search prompt for the preview source
+    pub fn for_preview(origin_line: usize, previous_search: Option<Search>) -> Self {
+        Self {
+            in_preview: true,
+            ..Self::new(origin_line, previous_search)
+        }
+    }
+
+    pub fn query(&self) -> SearchQuery {
+        SearchQuery {
+            pattern: self.textarea.lines().join(""),
+            case_insensitive: self.case_insensitive,
+            regex: self.regex,
+        }
+    }
+}
+
+/// Filter prompt state
+pub struct FilterState {
+    pub textarea: TextArea<'static>,
+    /// Parse error for the current expression
+    pub error: Option<String>,
+}
+
+impl FilterState {
+    pub fn new(expression: &str) -> Self {
+        let mut textarea = TextArea::default();
+        textarea.insert_str(expression);
+
+        Self {
+            textarea,
+            error: None,
+        }
+    }
+
+    pub fn expression(&self) -> String {
+        self.textarea.lines().join("")
+    }
+}
+
+/// Report picker state (findings loaded from a gitleaks JSON report)
+pub struct ReportState {
+    pub findings: Vec<Finding>,
+    /// Selection flag per finding
+    pub selected: Vec<bool>,
+    /// Whether the finding's fingerprint is already in the ignore file
+    pub ignored: Vec<bool>,
+    /// Index of the highlighted finding
+    pub cursor: usize,
+}
+
+impl ReportState {
+    pub fn new(findings: Vec<Finding>, file_context: &FileContext) -> Self {
+        let selected = vec![false; findings.len()];
+        let mut state = Self {
+            findings,
+            selected,
+            ignored: Vec::new(),
+            cursor: 0,
+        };
+        state.refresh_ignored(file_context);
+        state
+    }
+
+    /// Recompute which findings are already present in the ignore file
+    ///
+    /// Fingerprints are compared in normalized form, so an entry whose commit hash
+    /// differs only in case counts as present.
+    pub fn refresh_ignored(&mut self, file_context: &FileContext) {
+        let existing: HashSet<String> = file_context.lines.iter().map(line_key).collect();
+        self.ignored = self
+            .findings
+            .iter()
+            .map(|f| existing.contains(&fingerprint_key(&f.fingerprint)))
+            .collect();
+    }
+
+    /// Toggle selection of the highlighted finding
+    pub fn toggle_current(&mut self) {
+        if self.cursor < self.findings.len() && !self.ignored[self.cursor] {
+            self.selected[self.cursor] = !self.selected[self.cursor];
+        }
+    }
+
+    /// Select all findings not yet ignored, or clear the selection if all are selected
+    pub fn toggle_all(&mut self) {
+        let all_selected = self
+            .selected
+            .iter()
+            .zip(&self.ignored)
+            .all(|(selected, ignored)| *selected || *ignored);
+        for (selected, ignored) in self.selected.iter_mut().zip(&self.ignored) {
+            *selected = !all_selected && !ignored;
+        }
+    }
+
+    pub fn selected_count(&self) -> usize {
+        self.selected.iter().filter(|s| **s).count()
+    }
+}
+
+/// Action that produced the pending change of a conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSource {
+    /// A new edit
+    Edit,
+    /// Undoing the most recent change
+    Undo,
+    /// Redoing the most recently undone change
+    Redo,
+}
+
+/// Write conflict state: the file changed on disk while a change was pending (T043)
+pub struct ConflictState {
+    /// Change that could not be written
+    pub change: Change,
+    pub source: ConflictSource,
+    /// Differences from the file on disk to the file as it would be written, with
+    /// context; None marks skipped unchanged lines
+    pub hunks: Vec<Option<DiffLine>>,
+    pub scroll: usize,
+    /// Why the last merge attempt failed
+    pub error: Option<String>,
+}
+
+impl ConflictState {
+    /// Unchanged lines shown around each difference
+    const CONTEXT_LINES: usize = 2;
+
+    pub fn new(
+        change: Change,
+        source: ConflictSource,
+        disk: &FileContext,
+        ours: &FileContext,
+    ) -> Self {
+        let disk_lines: Vec<&str> = disk.lines.iter().map(|l| l.content()).collect();
+        let our_lines: Vec<&str> = ours.lines.iter().map(|l| l.content()).collect();
+        let hunks = diff::diff_hunks(&disk_lines, &our_lines, Self::CONTEXT_LINES);
+
+        Self {
+            change,
+            source,
+            hunks,
+            scroll: 0,
+            error: None,
+        }
+    }
+}
+
+/// Backup browser state
+pub struct BackupState {
+    /// Backups of the file, newest first
+    pub entries: Vec<BackupEntry>,
+    /// Index of the highlighted backup
+    pub cursor: usize,
+    /// Differences from the current file to the highlighted backup, with context; None
+    /// marks skipped unchanged lines
+    pub hunks: Vec<Option<DiffLine>>,
+    pub scroll: usize,
+    /// Why the highlighted backup could not be loaded
+    pub error: Option<String>,
+}
+
+impl BackupState {
+    /// Unchanged lines shown around each difference
+    const CONTEXT_LINES: usize = 2;
+
+    pub fn new(entries: Vec<BackupEntry>) -> Self {
+        Self {
+            entries,
+            cursor: 0,
+            hunks: Vec::new(),
+            scroll: 0,
+            error: None,
+        }
+    }
+
+    /// Recompute the diff for the highlighted backup
+    pub fn refresh_diff(&mut self, backup_manager: &BackupManager, file_context: &FileContext) {
+        self.scroll = 0;
+        self.hunks.clear();
+        self.error = None;
+
+        let Some(entry) = self.entries.get(self.cursor) else {
+            return;
+        };
+        match backup_manager.load_backup(&file_context.file_path, entry) {
+            Ok(backup) => {
+                let current: Vec<&str> =
+                    file_context.lines.iter().map(|l| l.content()).collect();
+                let restored: Vec<&str> = backup.lines.iter().map(|l| l.content()).collect();
+                self.hunks = diff::diff_hunks(&current, &restored, Self::CONTEXT_LINES);
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+}
+
 /// Application mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppMode {
     View,
     Edit,
+    /// Picking findings from a gitleaks report
+    Report,
+    /// Typing a search pattern
+    Search,
+    /// Typing a field filter expression
+    Filter,
+    /// Selecting a range of lines (vim-like `V`)
+    Visual,
+    /// Resolving a write conflict with changes made on disk
+    Conflict,
+    /// Scrolling and searching the preview pane
+    Preview,
+    /// Browsing and restoring backups
+    Backups,
 }
 
 pub struct App {
@@ -263,35 +890,94 @@ pub struct App {
     view_state: ViewState,
     edit_state: Option<EditState>,
     read_only: bool,
+    /// Masked secrets cannot be revealed in the preview
+    no_reveal: bool,
     should_quit: bool,
     backup_manager: BackupManager,
     save_message: Option<String>,
     pending_delete: bool,
+    /// Waiting for y/n confirmation of "delete all stale"
+    pending_stale_delete: bool,
+    report_state: Option<ReportState>,
+    history: History,
+    search_state: Option<SearchState>,
+    filter_state: Option<FilterState>,
+    /// Lines copied by the last yank
+    register: Vec<String>,
+    conflict_state: Option<ConflictState>,
+    backup_state: Option<BackupState>,
+    /// Watches the file for changes by other processes (None if watching failed)
+    watcher: Option<FileWatcher>,
+    /// The file changed on disk and will be reloaded once no edit is pending
+    pending_reload: bool,
 }
 
 impl App {
-    pub fn new(file_path: PathBuf, line_spec: crate::LineSpec, read_only: bool) -> Result<Self> {
-        // Load file
-        let file_context = FileContext::load(file_path)?;
+    pub fn new(
+        file_path: PathBuf,
+        line_spec: crate::LineSpec,
+        read_only: bool,
+        report_path: Option<PathBuf>,
+        config: Config,
+    ) -> Result<Self> {
+        // Load file; undecodable lines are shown with U+FFFD and kept byte for byte
+        let file_context = FileContext::load_lossy(file_path)?;
+        let damaged = file_context.lines.iter().filter(|line| line.is_damaged()).count();
+        let save_message = (damaged > 0).then(|| {
+            format!(
+                "{} line(s) contain invalid {} (marked !); they are saved unchanged unless edited",
+                damaged,
+                file_context.encoding.name()
+            )
+        });
+
+        // Load gitleaks report findings, if requested
+        let report_state = match report_path {
+            Some(path) => Some(ReportState::new(report::load_report(path)?, &file_context)),
+            None => None,
+        };
+
+        // Watch for changes by other processes
+        let watcher = FileWatcher::new(&file_context.file_path).ok();
 
         // Calculate display range from line specification
         let (start_line, end_line) = line_spec.calculate_range(file_context.total_lines)?;
 
         // Create view state
-        let mut view_state = ViewState::new(file_context, start_line, end_line)?;
+        let mut view_state = ViewState::new(file_context, start_line, end_line, &config)?;
 
         // Initialize preview for the first line
+        if let Some(ref report_state) = report_state {
+            view_state.set_report_findings(&report_state.findings);
+        }
         view_state.update_preview();
 
+        let mode = if report_state.is_some() {
+            AppMode::Report
+        } else {
+            AppMode::View
+        };
+
         Ok(Self {
-            mode: AppMode::View,
+            mode,
             view_state,
             edit_state: None,
             read_only,
+            no_reveal: config.preview.no_reveal.unwrap_or(false),
             should_quit: false,
-            backup_manager: BackupManager::new(),
-            save_message: None,
+            backup_manager: BackupManager::with_options(config.backup.options()),
+            save_message,
             pending_delete: false,
+            pending_stale_delete: false,
+            report_state,
+            history: History::new(),
+            search_state: None,
+            filter_state: None,
+            register: Vec::new(),
+            conflict_state: None,
+            backup_state: None,
+            watcher,
+            pending_reload: false,
         })
     }
 
@@ -304,7 +990,7 @@ impl App {
 
         let line_number = self.view_state.current_line;
         if let Some(line) = self.view_state.file_context.get_line(line_number) {
-            let edit_state = EditState::new(line_number, line.content.clone());
+            let edit_state = EditState::new(line_number, line.content().to_string());
             self.edit_state = Some(edit_state);
             self.mode = AppMode::Edit;
             self.save_message = None;
@@ -313,70 +999,102 @@ impl App {
         Ok(())
     }
 
+    /// Open a new empty line below (or above) the current line and edit it
+    fn open_line(&mut self, below: bool) -> Result<()> {
+        if self.read_only {
+            self.save_message = Some("Read-only mode: editing disabled".to_string());
+            return Ok(());
+        }
+
+        let current_line = self.view_state.current_line;
+        let line_number = if below || current_line == 0 {
+            current_line + 1
+        } else {
+            current_line
+        };
+
+        // The line only exists in memory until the edit is saved
+        self.view_state
+            .file_context
+            .insert_line(line_number, String::new())?;
+        self.refresh_view(line_number)?;
+
+        self.edit_state = Some(EditState::new_line(line_number, current_line));
+        self.mode = AppMode::Edit;
+        self.save_message = None;
+
+        Ok(())
+    }
+
     /// Save edit and return to view mode (T037)
     fn save_edit(&mut self) -> Result<()> {
-        if let Some(edit_state) = &self.edit_state {
-            if !edit_state.has_changes() {
-                self.mode = AppMode::View;
-                self.edit_state = None;
-                return Ok(());
-            }
-
-            let line_number = edit_state.original_line;
-            let new_content = edit_state.get_content();
+        self.mode = AppMode::View;
+        let Some(edit_state) = self.edit_state.take() else {
+            return Ok(());
+        };
 
-            // Create backup (T038)
-            let backup_path = self
-                .backup_manager
-                .create_backup(&self.view_state.file_context.file_path)?;
+        if !edit_state.has_changes() {
+            return Ok(());
+        }
 
-            // Check for concurrent modifications (T042)
-            if self
-                .view_state
-                .file_context
-                .check_for_external_modifications()?
-            {
-                self.save_message = Some("Warning: File modified externally!".to_string());
-                // In a full implementation, show conflict resolution modal (T043)
-                // For now, we'll just warn and proceed
-            }
+        let line_number = edit_state.original_line;
+        let new_content = edit_state.get_content();
 
-            // Update the line in file context
-            self.view_state
-                .file_context
-                .update_line(line_number, new_content.clone())?;
+        let change = if edit_state.inserted {
+            // Drop the placeholder line; the change inserts the line for real
+            self.view_state.file_context.delete_line(line_number)?;
+            Change::new(
+                format!("insert line {}", line_number),
+                vec![EditOperation::insert(line_number, new_content)],
+            )
+        } else {
+            Change::new(
+                format!("edit line {}", line_number),
+                vec![EditOperation::update(
+                    line_number,
+                    edit_state.original_content,
+                    new_content,
+                )],
+            )
+        };
 
-            // Write atomically (T015 already implemented)
-            self.view_state.file_context.write_atomic()?;
+        // Create backup and write atomically (T038, T015), unless the file changed on disk (T042)
+        let Some(backup_path) = self.commit_change(change)? else {
+            return Ok(());
+        };
 
-            // Update visible range
-            let (start, end) = (
-                self.view_state.visible_range.start_line,
-                self.view_state.visible_range.end_line,
-            );
-            let lines = self.view_state.file_context.get_range(start, end)?;
-            self.view_state.visible_range = LineRange::new(start, end, lines);
+        // Update visible range (the edited line may no longer match the filter)
+        self.refresh_view(line_number)?;
 
-            self.save_message = Some(format!(
-                "Saved line {} (backup: {})",
-                line_number,
-                backup_path
-                    .file_name()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("created")
-            ));
-        }
+        self.save_message = Some(format!(
+            "{} line {} (backup: {})",
+            if edit_state.inserted { "Inserted" } else { "Saved" },
+            line_number,
+            backup_path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("created")
+        ));
 
-        self.mode = AppMode::View;
-        self.edit_state = None;
         Ok(())
     }
 
     /// Cancel edit and return to view mode (T041)
-    fn cancel_edit(&mut self) {
+    fn cancel_edit(&mut self) -> Result<()> {
         self.mode = AppMode::View;
-        self.edit_state = None;
+
+        // Discard a line opened with o/O
+        if let Some(edit_state) = self.edit_state.take() {
+            if edit_state.inserted {
+                self.view_state
+                    .file_context
+                    .delete_line(edit_state.original_line)?;
+                self.refresh_view(edit_state.return_line)?;
+            }
+        }
+
         self.save_message = Some("Edit cancelled".to_string());
+        Ok(())
     }
 
     /// Delete the current line
@@ -394,27 +1112,34 @@ impl App {
             return Ok(());
         }
 
-        // Create backup before deletion
-        let backup_path = self
-            .backup_manager
-            .create_backup(&self.view_state.file_context.file_path)?;
-
-        // Delete the line
-        self.view_state.file_context.delete_line(line_number)?;
+        // Nothing selected (no lines match the filter)
+        if line_number == 0 {
+            self.save_message = Some("No line selected".to_string());
+            return Ok(());
+        }
 
-        // Write the changes atomically
-        self.view_state.file_context.write_atomic()?;
+        let content = self
+            .view_state
+            .file_context
+            .get_line(line_number)
+            .map(|line| line.content().to_string())
+            .unwrap_or_default();
+
+        // Delete the line (creates backup before deletion)
+        let change = Change::new(
+            format!("delete line {}", line_number),
+            vec![EditOperation::delete(line_number, content)],
+        );
+        let Some(backup_path) = self.commit_change(change)? else {
+            return Ok(());
+        };
 
-        // Adjust current_line if we deleted the last line
-        if line_number > self.view_state.file_context.total_lines {
-            self.view_state.current_line = self.view_state.file_context.total_lines;
-        }
+        // Update visible range to reflect the deletion; the following line moves up to
+        // line_number (or the cursor moves to the new last line)
+        self.refresh_view(line_number)?;
 
         // Handle empty file after deletion
         if self.view_state.file_context.total_lines == 0 {
-            self.view_state.current_line = 0;
-            self.view_state.visible_range = LineRange::new(0, 0, Vec::new());
-            self.view_state.preview_content = None; // Clear preview for empty file
             self.save_message = Some(format!(
                 "Deleted line {} (backup: {}). File is now empty.",
                 line_number,
@@ -426,25 +1151,1225 @@ impl App {
             return Ok(());
         }
 
-        // Update visible range to reflect the deletion
-        let (start, end) = (
-            self.view_state.visible_range.start_line,
-            self.view_state.visible_range.end_line.min(self.view_state.file_context.total_lines),
-        );
+        self.save_message = Some(format!(
+            "Deleted line {} (backup: {})",
+            line_number,
+            backup_path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("created")
+        ));
+
+        Ok(())
+    }
+
+    /// Undo the most recent change
+    fn undo(&mut self) -> Result<()> {
+        self.apply_history(true)
+    }
+
+    /// Redo the most recently undone change
+    fn redo(&mut self) -> Result<()> {
+        self.apply_history(false)
+    }
+
+    /// Revert (undo) or re-apply (redo) a change from history and write it to disk
+    fn apply_history(&mut self, undo: bool) -> Result<()> {
+        if self.read_only {
+            self.save_message = Some("Read-only mode: editing disabled".to_string());
+            return Ok(());
+        }
+
+        // The change that undo/redo is about to make to the file
+        let (pending, source) = if undo {
+            let pending = self
+                .history
+                .next_undo()
+                .map(|change| change.inverse(format!("undo {}", change.description)));
+            (pending, ConflictSource::Undo)
+        } else {
+            let pending = self.history.next_redo().map(|change| {
+                Change::new(format!("redo {}", change.description), change.operations.clone())
+            });
+            (pending, ConflictSource::Redo)
+        };
+        let Some(pending) = pending else {
+            self.save_message = Some(
+                if undo { "Already at oldest change" } else { "Already at newest change" }
+                    .to_string(),
+            );
+            return Ok(());
+        };
+
+        // Undo/redo would overwrite the other writer's changes
+        if self
+            .view_state
+            .file_context
+            .check_for_external_modifications()?
+            && self.open_conflict(pending.clone(), source)?
+        {
+            return Ok(());
+        }
+
+        let file_context = &mut self.view_state.file_context;
+        let result = if undo {
+            self.history.undo(file_context)
+        } else {
+            self.history.redo(file_context)
+        };
+
+        let change = match result {
+            Ok(Some(change)) => change,
+            // The history was checked above
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                let action = if undo { "undo" } else { "redo" };
+                self.save_message = Some(format!("Cannot {}: {}", action, e));
+                return Ok(());
+            }
+        };
+
+        // Back up only once the change applied, so a failed undo/redo leaves no backup.
+        // The file on disk is untouched until write_atomic below.
+        let backup_path = match self
+            .backup_manager
+            .create_backup(&self.view_state.file_context.file_path, &pending.description)
+        {
+            Ok(path) => path,
+            Err(e) => {
+                // Step the buffer and history back so they match the file again
+                let file_context = &mut self.view_state.file_context;
+                if undo {
+                    self.history.redo(file_context)?;
+                } else {
+                    self.history.undo(file_context)?;
+                }
+                return Err(e);
+            }
+        };
+
+        // Write the changes atomically
+        self.view_state.file_context.write_atomic()?;
+
+        self.refresh_view(change.anchor_line())?;
+        let made = change
+            .made_at()
+            .map_or(String::new(), |time| format!(", made {}", elapsed_label(time)));
+        self.save_message = Some(format!(
+            "{}: {}{} (backup: {})",
+            if undo { "Undid" } else { "Redid" },
+            change.description,
+            made,
+            backup_path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("created")
+        ));
+
+        Ok(())
+    }
+
+    /// Apply a change to the file and write it atomically, creating a backup first
+    ///
+    /// If the file was modified on disk since it was loaded, nothing is written and the
+    /// conflict view opens instead (T043); returns the backup path otherwise.
+    fn commit_change(&mut self, change: Change) -> Result<Option<PathBuf>> {
+        if self
+            .view_state
+            .file_context
+            .check_for_external_modifications()?
+            && self.open_conflict(change.clone(), ConflictSource::Edit)?
+        {
+            return Ok(None);
+        }
+
+        let backup_path = self
+            .backup_manager
+            .create_backup(&self.view_state.file_context.file_path, &change.description)?;
+
+        change.apply(&mut self.view_state.file_context)?;
+        self.view_state.file_context.write_atomic()?;
+        self.history.record(change);
+
+        Ok(Some(backup_path))
+    }
+
+    /// Open the conflict view for a change that could not be written
+    ///
+    /// Returns false (and opens nothing) if the file on disk only had its timestamp
+    /// updated and its content still matches the loaded file.
+    fn open_conflict(&mut self, change: Change, source: ConflictSource) -> Result<bool> {
+        let file_context = &mut self.view_state.file_context;
+        let disk = FileContext::load_lossy(&file_context.file_path)?;
+
+        let unchanged = disk
+            .lines
+            .iter()
+            .map(|line| line.content())
+            .eq(file_context.lines.iter().map(|line| line.content()));
+        if unchanged {
+            file_context.refresh_metadata()?;
+            return Ok(false);
+        }
+
+        let mut ours = file_context.clone();
+        change.apply(&mut ours)?;
+
+        self.conflict_state = Some(ConflictState::new(change, source, &disk, &ours));
+        self.mode = AppMode::Conflict;
+        self.save_message = None;
+        Ok(true)
+    }
+
+    /// Resolve a conflict by discarding the pending change and reloading the file
+    fn conflict_reload(&mut self) -> Result<()> {
+        let Some(conflict) = self.conflict_state.take() else {
+            return Ok(());
+        };
+
+        self.mode = AppMode::View;
+        let disk = FileContext::load_lossy(&self.view_state.file_context.file_path)?;
+        self.reload(disk)?;
+        self.save_message = Some(format!(
+            "Reloaded from disk, discarded: {}",
+            conflict.change.description
+        ));
+
+        Ok(())
+    }
+
+    /// Close the conflict view without writing, leaving the buffer and the file on disk as
+    /// they are
+    fn conflict_cancel(&mut self) -> Result<()> {
+        let Some(conflict) = self.conflict_state.take() else {
+            return Ok(());
+        };
+
+        self.mode = AppMode::View;
+        self.refresh_view(self.view_state.current_line)?;
+        self.save_message = Some(format!("Cancelled: {}", conflict.change.description));
+        Ok(())
+    }
+
+    /// Resolve a conflict by writing the pending change over the file on disk
+    fn conflict_overwrite(&mut self) -> Result<()> {
+        let Some(conflict) = self.conflict_state.take() else {
+            return Ok(());
+        };
+
+        let backup_path = self
+            .backup_manager
+            .create_backup(
+                &self.view_state.file_context.file_path,
+                &format!("{} (overwriting changes on disk)", conflict.change.description),
+            )?;
+
+        // Undo/redo move their change between the history stacks; the buffer is as it
+        // was when the conflict opened, so they cannot fail here
+        let anchor_line = conflict.change.anchor_line();
+        let file_context = &mut self.view_state.file_context;
+        match conflict.source {
+            ConflictSource::Edit => {
+                conflict.change.apply(file_context)?;
+                self.history.record(conflict.change);
+            }
+            ConflictSource::Undo => {
+                self.history.undo(file_context)?;
+            }
+            ConflictSource::Redo => {
+                self.history.redo(file_context)?;
+            }
+        }
+        self.view_state.file_context.write_atomic()?;
+
+        self.finish_conflict(
+            anchor_line,
+            format!(
+                "Overwrote changes on disk (backup: {})",
+                backup_path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("created")
+            ),
+        )
+    }
+
+    /// Resolve a conflict by re-applying the pending change on top of the file on disk
+    fn conflict_merge(&mut self) -> Result<()> {
+        let Some(mut conflict) = self.conflict_state.take() else {
+            return Ok(());
+        };
+
+        let disk = FileContext::load_lossy(&self.view_state.file_context.file_path)?;
+        let rebased = match conflict.change.rebase(&self.view_state.file_context, &disk) {
+            Ok(rebased) => rebased,
+            Err(e) => {
+                let reason = match e {
+                    GliError::ConcurrentModification(msg) => msg,
+                    other => other.to_string(),
+                };
+                conflict.error = Some(format!("Cannot merge: {}", reason));
+                self.conflict_state = Some(conflict);
+                return Ok(());
+            }
+        };
+
+        let backup_path = self
+            .backup_manager
+            .create_backup(
+                &self.view_state.file_context.file_path,
+                &format!("{} (merged with changes on disk)", rebased.description),
+            )?;
+
+        self.view_state.file_context = disk;
+        rebased.apply(&mut self.view_state.file_context)?;
+        self.view_state.file_context.write_atomic()?;
+
+        // Line numbers of earlier changes no longer match the merged file
+        self.history.clear();
+        let anchor_line = rebased.anchor_line();
+        self.history.record(rebased);
+
+        self.finish_conflict(
+            anchor_line,
+            format!(
+                "Merged {} into the file on disk (backup: {})",
+                conflict.change.description,
+                backup_path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("created")
+            ),
+        )
+    }
+
+    /// Return to view mode after a conflict was resolved
+    fn finish_conflict(&mut self, target_line: usize, message: String) -> Result<()> {
+        self.mode = AppMode::View;
+
+        if let Some(ref mut report_state) = self.report_state {
+            report_state.refresh_ignored(&self.view_state.file_context);
+        }
+
+        self.refresh_view(target_line)?;
+        self.save_message = Some(message);
+        Ok(())
+    }
+
+    /// Reload the file if another process changed it
+    ///
+    /// Reloading waits while an edit, selection or conflict is pending.
+    fn check_external_changes(&mut self) -> Result<()> {
+        if self.watcher.as_ref().is_some_and(|watcher| watcher.has_changed()) {
+            self.pending_reload = true;
+        }
+
+        if !self.pending_reload || !matches!(self.mode, AppMode::View | AppMode::Report) {
+            return Ok(());
+        }
+        self.pending_reload = false;
+
+        // Events for our own writes leave the modification time unchanged
+        let file_path = self.view_state.file_context.file_path.clone();
+        match self.view_state.file_context.check_for_external_modifications() {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(_) => {
+                self.save_message = Some(format!("{} was removed on disk", file_path.display()));
+                return Ok(());
+            }
+        }
+
+        let disk = match FileContext::load_lossy(&file_path) {
+            Ok(disk) => disk,
+            Err(e) => {
+                self.save_message = Some(format!("Cannot reload: {}", e));
+                return Ok(());
+            }
+        };
+
+        let (added, removed) = self.reload(disk)?;
+        self.save_message = Some(format!(
+            "Reloaded: file changed on disk (+{} -{} lines)",
+            added, removed
+        ));
+
+        Ok(())
+    }
+
+    /// Replace the file with a freshly loaded version
+    ///
+    /// The cursor stays on the same entry if it still exists (otherwise on the entry that
+    /// took its place). Undo history is cleared since its line numbers no longer apply.
+    /// Returns the number of added and removed lines.
+    fn reload(&mut self, disk: FileContext) -> Result<(usize, usize)> {
+        let old_lines: Vec<&str> = self
+            .view_state
+            .file_context
+            .lines
+            .iter()
+            .map(|line| line.content())
+            .collect();
+        let new_lines: Vec<&str> = disk.lines.iter().map(|line| line.content()).collect();
+        let diff = diff::diff_lines(&old_lines, &new_lines);
+
+        let added = diff.iter().filter(|line| line.kind == DiffKind::Added).count();
+        let removed = diff.iter().filter(|line| line.kind == DiffKind::Removed).count();
+
+        // First line of the new version at or after the cursor's position in the diff
+        let current_line = self.view_state.current_line;
+        let anchor_line = diff
+            .iter()
+            .skip_while(|line| line.old_line.is_none_or(|old| old < current_line))
+            .find_map(|line| line.new_line)
+            .unwrap_or(disk.total_lines);
+
+        self.view_state.file_context = disk;
+        self.history.clear();
+
+        if let Some(ref mut report_state) = self.report_state {
+            report_state.refresh_ignored(&self.view_state.file_context);
+        }
+
+        self.refresh_view(anchor_line)?;
+        Ok((added, removed))
+    }
+
+    /// Re-sync the view with the file after a modification or filter change
+    ///
+    /// Places the cursor on `target_line`, or on the next displayed line if it is hidden
+    /// by the filter. The viewport only moves if the cursor would end up outside of it.
+    fn refresh_view(&mut self, target_line: usize) -> Result<()> {
+        self.view_state.apply_filter();
+        let total_rows = self.view_state.total_rows();
+
+        // Handle empty file (or no lines matching the filter)
+        if total_rows == 0 {
+            self.view_state.current_line = 0;
+            self.view_state.visible_range = LineRange::new(0, 0);
+            self.view_state.preview_content = None;
+            self.view_state.preview_loader.cancel();
+            return Ok(());
+        }
+
+        let target_row = self.view_state.nearest_row(target_line);
+        let height = self.view_state.viewport_height.max(1);
+
+        let mut start = self.view_state.visible_range.start_line.clamp(1, total_rows);
+        if target_row < start || target_row >= start + height {
+            start = target_row.saturating_sub(height / 2).max(1);
+        }
+        let end = (start + height - 1).min(total_rows);
+        // Keep the page full when the end of the file is reached
+        let start = start.min(end.saturating_sub(height - 1).max(1));
+
+        self.update_visible_range(start, end)?;
+        self.view_state.current_line = self.view_state.line_at_row(target_row);
+        self.view_state.update_preview();
+        Ok(())
+    }
+
+    /// Open the search prompt
+    fn enter_search_mode(&mut self) {
+        let previous_search = self.view_state.search.clone();
+        self.search_state = Some(SearchState::new(self.view_state.current_line, previous_search));
+        self.mode = AppMode::Search;
+        self.save_message = None;
+    }
+
+    /// Re-run the search after the pattern or options changed
+    ///
+    /// Moves the cursor to the first match at or after the line where the prompt was opened.
+    fn update_incremental_search(&mut self) -> Result<()> {
+        let Some(ref mut search_state) = self.search_state else {
+            return Ok(());
+        };
+
+        if search_state.in_preview {
+            self.update_incremental_preview_search();
+            return Ok(());
+        }
+
+        let origin_line = search_state.origin_line;
+
+        let target_line = match Search::compile(search_state.query()) {
+            Ok(Some(search)) => {
+                search_state.error = None;
+                let origin_row = self.view_state.nearest_row(origin_line);
+                let found = self
+                    .view_state
+                    .find_row(origin_row, true, true, |line| search.is_match(line.content()))
+                    .map(|row| self.view_state.line_at_row(row));
+                self.view_state.search = Some(search);
+                found.unwrap_or(origin_line)
+            }
+            Ok(None) => {
+                search_state.error = None;
+                self.view_state.search = None;
+                origin_line
+            }
+            Err(e) => {
+                search_state.error = Some(e.to_string());
+                return Ok(());
+            }
+        };
+
+        if self.view_state.total_rows() > 0 {
+            self.jump_to_line(target_line)?;
+        }
+        Ok(())
+    }
+
+    /// Incremental search in the preview source
+    ///
+    /// Scrolls the preview to the first match at or after the line it was scrolled to when
+    /// the prompt was opened.
+    fn update_incremental_preview_search(&mut self) {
+        let Some(ref mut search_state) = self.search_state else {
+            return;
+        };
+
+        let origin_line = search_state.origin_line;
+        let target_line = match Search::compile(search_state.query()) {
+            Ok(Some(search)) => {
+                search_state.error = None;
+                let found = self.view_state.preview_content.as_ref().and_then(|preview| {
+                    preview.find_match(&search, origin_line, true, true)
+                });
+                self.view_state.preview_search = Some(search);
+                found.unwrap_or(origin_line)
+            }
+            Ok(None) => {
+                search_state.error = None;
+                self.view_state.preview_search = None;
+                origin_line
+            }
+            Err(e) => {
+                search_state.error = Some(e.to_string());
+                return;
+            }
+        };
+
+        self.view_state.scroll_preview_to(target_line);
+    }
+
+    /// Accept the search pattern and return to view (or preview) mode
+    fn confirm_search(&mut self) {
+        let search_state = self.search_state.take();
+        let in_preview = search_state.as_ref().is_some_and(|s| s.in_preview);
+        self.mode = if in_preview {
+            AppMode::Preview
+        } else {
+            AppMode::View
+        };
+
+        if let Some(error) = search_state.and_then(|s| s.error) {
+            self.save_message = Some(error);
+            return;
+        }
+
+        self.save_message = if in_preview {
+            self.preview_search_status(false)
+        } else {
+            self.search_status(false)
+        };
+    }
+
+    /// Cancel the search prompt, restoring the previous search and cursor position
+    fn cancel_search(&mut self) -> Result<()> {
+        self.mode = AppMode::View;
+        if let Some(search_state) = self.search_state.take() {
+            if search_state.in_preview {
+                self.mode = AppMode::Preview;
+                self.view_state.preview_search = search_state.previous_search;
+                self.view_state.scroll_preview_to(search_state.origin_line);
+                self.save_message = None;
+                return Ok(());
+            }
+            self.view_state.search = search_state.previous_search;
+            if self.view_state.total_rows() > 0 {
+                self.jump_to_line(search_state.origin_line)?;
+            }
+        }
+        self.save_message = None;
+        Ok(())
+    }
+
+    /// Jump to the next (or previous) match of the active search
+    fn search_next(&mut self, forward: bool) -> Result<()> {
+        let Some(ref search) = self.view_state.search else {
+            self.save_message = Some("No previous search".to_string());
+            return Ok(());
+        };
+
+        let current_row = self.view_state.current_row();
+        let Some(target_row) = self.view_state.find_row(current_row, forward, false, |line| {
+            search.is_match(line.content())
+        }) else {
+            self.save_message = Some(format!("Pattern not found: {}", search.query.pattern));
+            return Ok(());
+        };
+
+        let wrapped = if forward {
+            target_row <= current_row
+        } else {
+            target_row >= current_row
+        };
+
+        self.jump_to_line(self.view_state.line_at_row(target_row))?;
+        self.save_message = self.search_status(wrapped);
+        Ok(())
+    }
+
+    /// Focus the preview pane for scrolling and searching
+    fn enter_preview_mode(&mut self) {
+        if !self.view_state.preview_shown() {
+            self.save_message = Some("No preview for this line".to_string());
+            return;
+        }
+        self.mode = AppMode::Preview;
+        self.save_message = None;
+    }
+
+    /// Open the search prompt for the preview source
+    fn enter_preview_search_mode(&mut self) {
+        let previous_search = self.view_state.preview_search.clone();
+        self.search_state = Some(SearchState::for_preview(
+            self.view_state.preview_focus_line(),
+            previous_search,
+        ));
+        self.mode = AppMode::Search;
+        self.save_message = None;
+    }
+
+    /// Scroll the preview to the next (or previous) match of the preview search
+    fn preview_search_next(&mut self, forward: bool) {
+        let Some(ref search) = self.view_state.preview_search else {
+            self.save_message = Some("No previous search".to_string());
+            return;
+        };
+        let Some(ref preview) = self.view_state.preview_content else {
+            return;
+        };
+
+        let focus_line = self.view_state.preview_focus_line();
+        let Some(target_line) = preview.find_match(search, focus_line, forward, false) else {
+            self.save_message = Some(format!("Pattern not found: {}", search.query.pattern));
+            return;
+        };
+
+        let wrapped = if forward {
+            target_line <= focus_line
+        } else {
+            target_line >= focus_line
+        };
+
+        self.view_state.scroll_preview_to(target_line);
+        self.save_message = self.preview_search_status(wrapped);
+    }
+
+    /// Status message describing the current match position in the preview
+    fn preview_search_status(&self, wrapped: bool) -> Option<String> {
+        let search = self.view_state.preview_search.as_ref()?;
+        let preview = self.view_state.preview_content.as_ref()?;
+        let focus_line = self.view_state.preview_focus_line();
+
+        let mut position = 0;
+        let mut total = 0;
+        for (idx, line) in preview.source.lines.iter().enumerate() {
+            if search.is_match(line) {
+                total += 1;
+                if idx + 1 == focus_line {
+                    position = total;
+                }
+            }
+        }
+
+        if total == 0 {
+            return Some(format!("Pattern not found: {}", search.query.pattern));
+        }
+
+        Some(format!(
+            "/{} [{}/{}]{}",
+            search.query.pattern,
+            position,
+            total,
+            if wrapped { " (search wrapped)" } else { "" }
+        ))
+    }
+
+    /// Show or mask the secret in the preview again
+    fn toggle_reveal(&mut self) {
+        if self.no_reveal {
+            self.save_message = Some("Revealing secrets is disabled (--no-reveal)".to_string());
+            return;
+        }
+
+        self.view_state.secret_revealed = !self.view_state.secret_revealed;
+        self.save_message = Some(if self.view_state.secret_revealed {
+            "Secret revealed until you move to another entry (R to mask)".to_string()
+        } else {
+            "Secret masked".to_string()
+        });
+    }
+
+    /// Status message for a changed preview context
+    fn preview_context_status(&self) -> String {
+        format!("Preview context: ±{} lines", self.view_state.preview_context)
+    }
+
+    /// Status message describing the current match position
+    fn search_status(&self, wrapped: bool) -> Option<String> {
+        let search = self.view_state.search.as_ref()?;
+        let (position, total) = self
+            .view_state
+            .count_rows(|line| search.is_match(line.content()));
+
+        if total == 0 {
+            return Some(format!("Pattern not found: {}", search.query.pattern));
+        }
+
+        Some(format!(
+            "/{} [{}/{}]{}",
+            search.query.pattern,
+            position,
+            total,
+            if wrapped { " (search wrapped)" } else { "" }
+        ))
+    }
+
+    /// Open the filter prompt, pre-filled with the active filter
+    fn enter_filter_mode(&mut self) {
+        let expression = self
+            .view_state
+            .filter
+            .as_ref()
+            .map(|f| f.expression.clone())
+            .unwrap_or_default();
+        self.filter_state = Some(FilterState::new(&expression));
+        self.mode = AppMode::Filter;
+        self.save_message = None;
+    }
+
+    /// Parse the filter expression and apply it to the view
+    ///
+    /// Stays in filter mode if the expression is invalid.
+    fn confirm_filter(&mut self) -> Result<()> {
+        let Some(ref mut filter_state) = self.filter_state else {
+            return Ok(());
+        };
+
+        let filter = match FieldFilter::parse(&filter_state.expression()) {
+            Ok(filter) => filter,
+            Err(e) => {
+                filter_state.error = Some(e.to_string());
+                return Ok(());
+            }
+        };
+
+        self.filter_state = None;
+        self.mode = AppMode::View;
+        self.set_filter(filter)
+    }
+
+    /// Replace the active filter and re-sync the view
+    fn set_filter(&mut self, filter: Option<FieldFilter>) -> Result<()> {
+        let current_line = self.view_state.current_line;
+        self.view_state.set_filter(filter);
+        self.refresh_view(current_line)?;
+
+        self.save_message = Some(match self.view_state.filter {
+            Some(ref filter) if self.view_state.total_rows() == 0 => {
+                format!("No entries match filter: {}", filter.expression)
+            }
+            Some(ref filter) => format!(
+                "Filter: {} ({} of {} lines)",
+                filter.expression,
+                self.view_state.total_rows(),
+                self.view_state.file_context.total_lines
+            ),
+            None => "Filter cleared".to_string(),
+        });
+        Ok(())
+    }
+
+    /// Check all fingerprints for files or commits that no longer exist
+    fn validate_stale(&mut self) {
+        let report = stale::check_stale(
+            &self.view_state.file_context.lines,
+            self.view_state.repository.as_ref(),
+            &self.view_state.root,
+        );
+
+        let mut message = format!(
+            "{} stale of {} fingerprints",
+            report.stale.len(),
+            report.checked
+        );
+        if report.unchecked > 0 {
+            message.push_str(&format!(
+                " ({} commit entries unchecked: not in a git repository)",
+                report.unchecked
+            ));
+        }
+
+        self.save_message = Some(message);
+        self.view_state.stale_report = Some(report);
+    }
+
+    /// Line numbers of displayed stale entries
+    fn stale_lines(&self) -> Vec<usize> {
+        let Some(ref report) = self.view_state.stale_report else {
+            return Vec::new();
+        };
+
+        (1..=self.view_state.total_rows())
+            .map(|row| self.view_state.line_at_row(row))
+            .filter(|&line_number| {
+                self.view_state
+                    .file_context
+                    .get_line(line_number)
+                    .is_some_and(|line| report.is_stale(line.content()))
+            })
+            .collect()
+    }
+
+    /// Ask for confirmation before deleting all stale entries
+    fn request_stale_delete(&mut self) {
+        if self.read_only {
+            self.save_message = Some("Read-only mode: deletion disabled".to_string());
+            return;
+        }
+
+        // Validate first if it hasn't been done yet
+        if self.view_state.stale_report.is_none() {
+            self.validate_stale();
+        }
+
+        let count = self.stale_lines().len();
+        if count == 0 {
+            self.save_message = Some("No stale entries".to_string());
+            return;
+        }
+
+        self.pending_stale_delete = true;
+        self.save_message = Some(format!("Delete {} stale entries? (y/n)", count));
+    }
+
+    /// Delete all displayed stale entries in a single backed-up write
+    fn delete_stale_entries(&mut self) -> Result<()> {
+        let stale_lines = self.stale_lines();
+        if stale_lines.is_empty() {
+            self.save_message = Some("No stale entries".to_string());
+            return Ok(());
+        }
+
+        let description = format!("delete {} stale entries", stale_lines.len());
+        let Some(backup_name) = self.delete_lines(&stale_lines, description)? else {
+            return Ok(());
+        };
+        self.save_message = Some(format!(
+            "Deleted {} stale entries (backup: {})",
+            stale_lines.len(),
+            backup_name
+        ));
+
+        Ok(())
+    }
+
+    /// Remove later occurrences of duplicate fingerprints among the displayed lines
+    fn dedupe(&mut self) -> Result<()> {
+        if self.read_only {
+            self.save_message = Some("Read-only mode: deletion disabled".to_string());
+            return Ok(());
+        }
+
+        let duplicate_lines: Vec<usize> = self
+            .view_state
+            .file_context
+            .duplicates()
+            .later_occurrences()
+            .into_iter()
+            .filter(|&line_number| self.view_state.row_of_line(line_number).is_some())
+            .collect();
+
+        if duplicate_lines.is_empty() {
+            self.save_message = Some("No duplicate fingerprints".to_string());
+            return Ok(());
+        }
+
+        let description = format!("remove {} duplicate(s)", duplicate_lines.len());
+        let Some(backup_name) = self.delete_lines(&duplicate_lines, description)? else {
+            return Ok(());
+        };
+        self.save_message = Some(format!(
+            "Removed {} duplicate fingerprint(s) (backup: {})",
+            duplicate_lines.len(),
+            backup_name
+        ));
+
+        Ok(())
+    }
+
+    /// Delete several lines in a single backed-up write, recorded as one undoable change
+    ///
+    /// `line_numbers` must be in ascending order. Returns the backup file name, or None if
+    /// the file changed on disk and the conflict view was opened instead.
+    fn delete_lines(
+        &mut self,
+        line_numbers: &[usize],
+        description: String,
+    ) -> Result<Option<String>> {
+        // Delete from the bottom up so earlier line numbers stay valid
+        let operations = line_numbers
+            .iter()
+            .rev()
+            .map(|&line_number| {
+                let content = self
+                    .view_state
+                    .file_context
+                    .get_line(line_number)
+                    .map(|line| line.content().to_string())
+                    .unwrap_or_default();
+                EditOperation::delete(line_number, content)
+            })
+            .collect();
+
+        let Some(backup_path) = self.commit_change(Change::new(description, operations))? else {
+            return Ok(None);
+        };
+
+        // Keep the cursor on the same entry (or the next one if it was deleted)
+        let current_line = self.view_state.current_line;
+        let removed_above = line_numbers.iter().filter(|&&l| l < current_line).count();
+        self.refresh_view(current_line - removed_above)?;
+
+        Ok(Some(
+            backup_path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("created")
+                .to_string(),
+        ))
+    }
+
+    /// Start a visual-line selection at the current line
+    fn enter_visual_mode(&mut self) {
+        if self.view_state.current_line == 0 {
+            self.save_message = Some("No line selected".to_string());
+            return;
+        }
+
+        self.view_state.visual_anchor = Some(self.view_state.current_line);
+        self.mode = AppMode::Visual;
+        self.save_message = None;
+    }
+
+    /// Leave visual-line mode, returning the selected line numbers
+    fn exit_visual_mode(&mut self) -> Vec<usize> {
+        let selected = self.view_state.selected_lines();
+        self.view_state.visual_anchor = None;
+        self.mode = AppMode::View;
+        selected
+    }
+
+    /// Delete all selected lines
+    fn delete_selection(&mut self) -> Result<()> {
+        let selected = self.exit_visual_mode();
+        if self.read_only {
+            self.save_message = Some("Read-only mode: deletion disabled".to_string());
+            return Ok(());
+        }
+        if selected.is_empty() {
+            return Ok(());
+        }
+
+        let description = format!("delete {} lines", selected.len());
+        let Some(backup_name) = self.delete_lines(&selected, description)? else {
+            return Ok(());
+        };
+        self.save_message = Some(format!(
+            "Deleted {} line(s) (backup: {})",
+            selected.len(),
+            backup_name
+        ));
+
+        Ok(())
+    }
+
+    /// Comment out the selected lines, or uncomment them if all of them are comments
+    ///
+    /// Blank lines are left untouched.
+    fn toggle_comment_selection(&mut self) -> Result<()> {
+        let selected = self.exit_visual_mode();
+        if self.read_only {
+            self.save_message = Some("Read-only mode: editing disabled".to_string());
+            return Ok(());
+        }
+
+        let targets: Vec<(usize, String)> = selected
+            .iter()
+            .filter_map(|&line_number| {
+                let line = self.view_state.file_context.get_line(line_number)?;
+                (*line.pattern_type() != PatternType::BlankLine)
+                    .then(|| (line_number, line.content().to_string()))
+            })
+            .collect();
+
+        if targets.is_empty() {
+            self.save_message = Some("Nothing to comment".to_string());
+            return Ok(());
+        }
+
+        let uncomment = targets
+            .iter()
+            .all(|(_, content)| content.trim_start().starts_with('#'));
+
+        let count = targets.len();
+        let first_line = targets[0].0;
+        let operations = targets
+            .into_iter()
+            .map(|(line_number, content)| {
+                let new_content = if uncomment {
+                    uncomment_line(&content)
+                } else {
+                    format!("# {}", content)
+                };
+                EditOperation::update(line_number, content, new_content)
+            })
+            .collect();
+
+        let action = if uncomment { "uncomment" } else { "comment" };
+        let change = Change::new(format!("{} {} lines", action, count), operations);
+        let Some(backup_path) = self.commit_change(change)? else {
+            return Ok(());
+        };
+
+        self.refresh_view(first_line)?;
+        self.save_message = Some(format!(
+            "{} {} line(s) (backup: {})",
+            if uncomment { "Uncommented" } else { "Commented" },
+            count,
+            backup_path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("created")
+        ));
+
+        Ok(())
+    }
+
+    /// Copy the selected lines to the register and the system clipboard
+    fn yank_selection(&mut self) {
+        let selected = self.exit_visual_mode();
+
+        self.register = selected
+            .iter()
+            .filter_map(|&line_number| self.view_state.file_context.get_line(line_number))
+            .map(|line| line.content().to_string())
+            .collect();
+
+        let text: String = self.register.iter().map(|line| format!("{}\n", line)).collect();
+        self.save_message = Some(match clipboard::copy(&text) {
+            Ok(()) => format!("Yanked {} line(s)", self.register.len()),
+            Err(_) => format!("Yanked {} line(s) (clipboard unavailable)", self.register.len()),
+        });
+    }
+
+    /// Insert the yanked lines above the current line
+    fn paste_register(&mut self) -> Result<()> {
+        if self.read_only {
+            self.save_message = Some("Read-only mode: editing disabled".to_string());
+            return Ok(());
+        }
+
+        if self.register.is_empty() {
+            self.save_message = Some("Nothing to paste (select with V, then y)".to_string());
+            return Ok(());
+        }
+
+        let insert_at = self.view_state.current_line.max(1);
+        let operations = self
+            .register
+            .iter()
+            .enumerate()
+            .map(|(offset, content)| EditOperation::insert(insert_at + offset, content.clone()))
+            .collect();
+
+        let count = self.register.len();
+        let change = Change::new(format!("paste {} lines", count), operations);
+        let Some(backup_path) = self.commit_change(change)? else {
+            return Ok(());
+        };
+
+        self.refresh_view(insert_at)?;
+        self.save_message = Some(format!(
+            "Pasted {} line(s) (backup: {})",
+            count,
+            backup_path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("created")
+        ));
+
+        Ok(())
+    }
+
+    /// Open the report picker, if a report was loaded
+    fn enter_report_mode(&mut self) {
+        match self.report_state {
+            Some(ref mut report_state) => {
+                report_state.refresh_ignored(&self.view_state.file_context);
+                self.mode = AppMode::Report;
+                self.save_message = None;
+            }
+            None => {
+                self.save_message = Some("No report loaded (use --report <path>)".to_string());
+            }
+        }
+    }
+
+    /// Append fingerprints of the selected report findings to the file
+    fn append_selected_findings(&mut self) -> Result<()> {
+        if self.read_only {
+            self.save_message = Some("Read-only mode: editing disabled".to_string());
+            return Ok(());
+        }
+
+        let Some(ref report_state) = self.report_state else {
+            return Ok(());
+        };
+
+        // Collect selected fingerprints, skipping ones already present in the file
+        // (compared normalized, see ReportState::refresh_ignored)
+        let mut existing: HashSet<String> =
+            self.view_state.file_context.lines.iter().map(line_key).collect();
+        let fingerprints: Vec<String> = report_state
+            .findings
+            .iter()
+            .zip(&report_state.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(finding, _)| finding.fingerprint.trim().to_string())
+            .filter(|fingerprint| existing.insert(fingerprint_key(fingerprint)))
+            .collect();
+
+        if fingerprints.is_empty() {
+            self.save_message = Some("No new findings selected".to_string());
+            return Ok(());
+        }
+
+        let first_line = self.view_state.file_context.total_lines + 1;
+        let operations = fingerprints
+            .iter()
+            .enumerate()
+            .map(|(offset, fingerprint)| EditOperation::insert(first_line + offset, fingerprint.clone()))
+            .collect();
+
+        // Append (creates backup before appending)
+        let change = Change::new(format!("append {} fingerprint(s)", fingerprints.len()), operations);
+        let Some(backup_path) = self.commit_change(change)? else {
+            return Ok(());
+        };
+
+        if let Some(ref mut report_state) = self.report_state {
+            report_state.selected.iter_mut().for_each(|s| *s = false);
+            report_state.refresh_ignored(&self.view_state.file_context);
+        }
+
+        self.mode = AppMode::View;
+        self.refresh_view(first_line)?;
+        self.save_message = Some(format!(
+            "Appended {} fingerprint(s) (backup: {})",
+            fingerprints.len(),
+            backup_path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("created")
+        ));
+
+        Ok(())
+    }
+
+    /// Open the backup browser
+    fn enter_backup_mode(&mut self) -> Result<()> {
+        let file_path = &self.view_state.file_context.file_path;
+        let entries = self.backup_manager.list_backups(file_path)?;
+        if entries.is_empty() {
+            self.save_message = Some(format!(
+                "No backups of this file in {}",
+                self.backup_manager.backup_dir(file_path).display()
+            ));
+            return Ok(());
+        }
+
+        let mut backup_state = BackupState::new(entries);
+        backup_state.refresh_diff(&self.backup_manager, &self.view_state.file_context);
+        self.backup_state = Some(backup_state);
+        self.mode = AppMode::Backups;
+        self.save_message = None;
+        Ok(())
+    }
+
+    /// Move the backup browser cursor by `delta` entries, clamped to the list
+    fn move_backup_cursor(&mut self, delta: isize) {
+        let Some(ref mut backup_state) = self.backup_state else {
+            return;
+        };
+
+        let last = backup_state.entries.len().saturating_sub(1);
+        let cursor = backup_state.cursor.saturating_add_signed(delta).min(last);
+        if cursor != backup_state.cursor {
+            backup_state.cursor = cursor;
+            backup_state.refresh_diff(&self.backup_manager, &self.view_state.file_context);
+        }
+    }
+
+    /// Scroll the backup diff by `delta` lines
+    fn scroll_backup_diff(&mut self, delta: isize) {
+        if let Some(ref mut backup_state) = self.backup_state {
+            let last = backup_state.hunks.len().saturating_sub(1);
+            backup_state.scroll = backup_state.scroll.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    /// Restore the highlighted backup, backing up the current file first
+    ///
+    /// The restore is a regular change: it is written atomically and can be undone.
+    fn restore_selected_backup(&mut self) -> Result<()> {
+        if self.read_only {
+            self.save_message = Some("Read-only mode: editing disabled".to_string());
+            return Ok(());
+        }
 
-        // Ensure start_line is valid after deletion
-        let start = start.min(self.view_state.file_context.total_lines).max(1);
-        let end = end.max(start); // Ensure end >= start
+        let Some(entry) = self
+            .backup_state
+            .as_ref()
+            .and_then(|backup_state| backup_state.entries.get(backup_state.cursor))
+            .cloned()
+        else {
+            return Ok(());
+        };
 
-        let lines = self.view_state.file_context.get_range(start, end)?;
-        self.view_state.visible_range = LineRange::new(start, end, lines);
+        let change = self
+            .backup_manager
+            .restore_change(&self.view_state.file_context, &entry)?;
+        if change.operations.is_empty() {
+            self.save_message = Some(format!("File already matches backup {}", entry.id));
+            return Ok(());
+        }
 
-        // Update preview for new current line
-        self.view_state.update_preview();
+        self.backup_state = None;
+        self.mode = AppMode::View;
+        let anchor_line = change.anchor_line();
+        let Some(backup_path) = self.commit_change(change)? else {
+            return Ok(());
+        };
 
+        self.refresh_view(anchor_line)?;
         self.save_message = Some(format!(
-            "Deleted line {} (backup: {})",
-            line_number,
+            "Restored backup {} from before: {} (backup: {})",
+            entry.id,
+            entry.operation,
             backup_path
                 .file_name()
                 .and_then(|s| s.to_str())
@@ -456,20 +2381,23 @@ impl App {
 
     /// Scroll up by one line (T044)
     fn scroll_up(&mut self) -> Result<()> {
+        let current_row = self.view_state.current_row();
+
         // Move cursor up if not at top of file
-        if self.view_state.current_line > 1 {
-            self.view_state.current_line -= 1;
+        if current_row > 1 {
+            let new_row = current_row - 1;
+            self.view_state.current_line = self.view_state.line_at_row(new_row);
 
             // Calculate scroll margin (lines from top/bottom before scrolling)
-            let scroll_margin = 3;
+            let scroll_margin = self.view_state.scroll_margin;
             let page_size = self.view_state.visible_range.end_line - self.view_state.visible_range.start_line;
 
             // Scroll viewport if cursor approaches top edge
-            let distance_from_top = self.view_state.current_line.saturating_sub(self.view_state.visible_range.start_line);
+            let distance_from_top = new_row.saturating_sub(self.view_state.visible_range.start_line);
             if distance_from_top < scroll_margin && self.view_state.visible_range.start_line > 1 {
                 // Scroll viewport up by one line
                 let new_start = self.view_state.visible_range.start_line.saturating_sub(1);
-                let new_end = (new_start + page_size).min(self.view_state.file_context.total_lines);
+                let new_end = (new_start + page_size).min(self.view_state.total_rows());
                 self.update_visible_range(new_start, new_end)?;
             }
 
@@ -481,20 +2409,24 @@ impl App {
 
     /// Scroll down by one line (T045)
     fn scroll_down(&mut self) -> Result<()> {
+        let current_row = self.view_state.current_row();
+        let total_rows = self.view_state.total_rows();
+
         // Move cursor down if not at end of file
-        if self.view_state.current_line < self.view_state.file_context.total_lines {
-            self.view_state.current_line += 1;
+        if current_row < total_rows {
+            let new_row = current_row + 1;
+            self.view_state.current_line = self.view_state.line_at_row(new_row);
 
             // Calculate scroll margin (lines from top/bottom before scrolling)
-            let scroll_margin = 3;
+            let scroll_margin = self.view_state.scroll_margin;
             let page_size = self.view_state.visible_range.end_line - self.view_state.visible_range.start_line;
 
             // Scroll viewport if cursor approaches bottom edge
-            let distance_from_bottom = self.view_state.visible_range.end_line.saturating_sub(self.view_state.current_line);
-            if distance_from_bottom < scroll_margin && self.view_state.visible_range.end_line < self.view_state.file_context.total_lines {
+            let distance_from_bottom = self.view_state.visible_range.end_line.saturating_sub(new_row);
+            if distance_from_bottom < scroll_margin && self.view_state.visible_range.end_line < total_rows {
                 // Scroll viewport down by one line
                 let new_start = self.view_state.visible_range.start_line + 1;
-                let new_end = (new_start + page_size).min(self.view_state.file_context.total_lines);
+                let new_end = (new_start + page_size).min(total_rows);
                 self.update_visible_range(new_start, new_end)?;
             }
 
@@ -506,6 +2438,10 @@ impl App {
 
     /// Scroll up by page (T046)
     fn page_up(&mut self) -> Result<()> {
+        if self.view_state.total_rows() == 0 {
+            return Ok(());
+        }
+
         let page_size =
             self.view_state.visible_range.end_line - self.view_state.visible_range.start_line;
         let new_start = self
@@ -514,80 +2450,98 @@ impl App {
             .start_line
             .saturating_sub(page_size)
             .max(1);
-        let new_end = (new_start + page_size).min(self.view_state.file_context.total_lines);
+        let new_end = (new_start + page_size).min(self.view_state.total_rows());
         self.update_visible_range(new_start, new_end)?;
-        self.view_state.current_line = new_start;
+        self.view_state.current_line = self.view_state.line_at_row(new_start);
+        self.view_state.update_preview();
         Ok(())
     }
 
     /// Scroll down by page (T047)
     fn page_down(&mut self) -> Result<()> {
+        if self.view_state.total_rows() == 0 {
+            return Ok(());
+        }
+
         let page_size =
             self.view_state.visible_range.end_line - self.view_state.visible_range.start_line;
 
         // Calculate new_start, but ensure we don't go beyond what allows a valid range
-        let total_lines = self.view_state.file_context.total_lines;
+        let total_rows = self.view_state.total_rows();
         let new_start = (self.view_state.visible_range.start_line + page_size)
-            .min(total_lines.saturating_sub(page_size).max(1));
-        let new_end = (new_start + page_size).min(total_lines);
+            .min(total_rows.saturating_sub(page_size).max(1));
+        let new_end = (new_start + page_size).min(total_rows);
 
         self.update_visible_range(new_start, new_end)?;
-        self.view_state.current_line = new_start;
+        self.view_state.current_line = self.view_state.line_at_row(new_start);
+        self.view_state.update_preview();
         Ok(())
     }
 
     /// Jump to top of file (T048)
     fn jump_to_top(&mut self) -> Result<()> {
+        if self.view_state.total_rows() == 0 {
+            return Ok(());
+        }
+
         let page_size =
             self.view_state.visible_range.end_line - self.view_state.visible_range.start_line;
-        let new_end = (1 + page_size).min(self.view_state.file_context.total_lines);
+        let new_end = (1 + page_size).min(self.view_state.total_rows());
         self.update_visible_range(1, new_end)?;
-        self.view_state.current_line = 1;
+        self.view_state.current_line = self.view_state.line_at_row(1);
+        self.view_state.update_preview();
         Ok(())
     }
 
     /// Jump to bottom of file (T049)
     fn jump_to_bottom(&mut self) -> Result<()> {
+        if self.view_state.total_rows() == 0 {
+            return Ok(());
+        }
+
         let page_size =
             self.view_state.visible_range.end_line - self.view_state.visible_range.start_line;
-        let new_start = (self
-            .view_state
-            .file_context
-            .total_lines
-            .saturating_sub(page_size - 1))
-        .max(1);
-        let new_end = self.view_state.file_context.total_lines;
-        self.update_visible_range(new_start, new_end)?;
-        self.view_state.current_line = new_end;
+        let total_rows = self.view_state.total_rows();
+        let new_start = total_rows.saturating_sub(page_size).max(1);
+        self.update_visible_range(new_start, total_rows)?;
+        self.view_state.current_line = self.view_state.line_at_row(total_rows);
+        self.view_state.update_preview();
         Ok(())
     }
 
     /// Jump to specific line (T050)
-    #[allow(dead_code)]
     fn jump_to_line(&mut self, target_line: usize) -> Result<()> {
         if target_line == 0 || target_line > self.view_state.file_context.total_lines {
             self.save_message = Some(format!("Invalid line number: {}", target_line));
             return Ok(());
         }
 
-        let page_size =
-            self.view_state.visible_range.end_line - self.view_state.visible_range.start_line;
+        let Some(target_row) = self.view_state.row_of_line(target_line) else {
+            self.save_message = Some(format!("Line {} is hidden by the filter", target_line));
+            return Ok(());
+        };
+
+        let page_size = self.view_state.viewport_height.saturating_sub(1);
         let half_page = page_size / 2;
 
-        let new_start = target_line.saturating_sub(half_page).max(1);
-        let new_end = (new_start + page_size).min(self.view_state.file_context.total_lines);
+        let new_start = target_row.saturating_sub(half_page).max(1);
+        let new_end = (new_start + page_size).min(self.view_state.total_rows());
 
         self.update_visible_range(new_start, new_end)?;
         self.view_state.current_line = target_line;
+        self.view_state.update_preview();
         self.save_message = Some(format!("Jumped to line {}", target_line));
         Ok(())
     }
 
     /// Update visible range helper (T053)
+    ///
+    /// `start` and `end` are rows: line numbers when unfiltered, positions among
+    /// matching lines when a filter is active.
     fn update_visible_range(&mut self, start: usize, end: usize) -> Result<()> {
-        let lines = self.view_state.file_context.get_range(start, end)?;
-        self.view_state.visible_range = LineRange::new(start, end, lines);
-        self.view_state.scroll_offset = start - 1;
+        self.view_state.check_rows(start, end)?;
+        self.view_state.visible_range = LineRange::new(start, end);
+        self.view_state.scroll_offset = start.saturating_sub(1);
         Ok(())
     }
 
@@ -627,21 +2581,24 @@ impl App {
             return Ok(());
         }
 
+        self.view_state.viewport_height = content_height;
+
+        // Nothing to lay out for an empty file
+        if self.view_state.total_rows() == 0 {
+            return Ok(());
+        }
+
         // Adjust visible range to screen size
-        let current_line = self.view_state.current_line;
-        let total_lines = self.view_state.file_context.total_lines;
+        let current_row = self.view_state.current_row();
+        let total_rows = self.view_state.total_rows();
 
         // Center current line in viewport
         let half_height = content_height / 2;
-        let new_start = current_line.saturating_sub(half_height).max(1);
-        let new_end = (new_start + content_height - 1).min(total_lines);
+        let new_start = current_row.saturating_sub(half_height).max(1);
+        let new_end = (new_start + content_height - 1).min(total_rows);
 
         // Update visible range
-        let lines = self.view_state.file_context.get_range(new_start, new_end)?;
-        self.view_state.visible_range = LineRange::new(new_start, new_end, lines);
-        self.view_state.scroll_offset = new_start - 1;
-
-        Ok(())
+        self.update_visible_range(new_start, new_end)
     }
 
     fn run_loop<B: ratatui::backend::Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
@@ -656,6 +2613,52 @@ impl App {
                         ViewerWidget::render_edit_mode(f, &self.view_state, edit_state);
                     }
                 }
+                AppMode::Search => {
+                    if let Some(ref search_state) = self.search_state {
+                        ViewerWidget::render_search_mode(f, &self.view_state, search_state);
+                    }
+                }
+                AppMode::Filter => {
+                    if let Some(ref filter_state) = self.filter_state {
+                        ViewerWidget::render_filter_mode(f, &self.view_state, filter_state);
+                    }
+                }
+                AppMode::Visual => {
+                    ViewerWidget::render_visual_mode(f, &self.view_state);
+                }
+                AppMode::Preview => {
+                    ViewerWidget::render_preview_mode(
+                        f,
+                        &self.view_state,
+                        self.save_message.as_deref(),
+                    );
+                }
+                AppMode::Conflict => {
+                    if let Some(ref conflict_state) = self.conflict_state {
+                        ConflictWidget::render(f, &self.view_state, conflict_state);
+                    }
+                }
+                AppMode::Backups => {
+                    if let Some(ref backup_state) = self.backup_state {
+                        BackupsWidget::render(
+                            f,
+                            &self.view_state,
+                            backup_state,
+                            self.read_only,
+                            self.save_message.as_deref(),
+                        );
+                    }
+                }
+                AppMode::Report => {
+                    if let Some(ref report_state) = self.report_state {
+                        ReportWidget::render(
+                            f,
+                            report_state,
+                            self.read_only,
+                            self.save_message.as_deref(),
+                        );
+                    }
+                }
             })?;
 
             // Handle input with 100ms polling (T034)
@@ -665,6 +2668,9 @@ impl App {
                 }
             }
 
+            self.check_external_changes()?;
+            self.view_state.poll_preview();
+
             // Check if we should quit
             if self.should_quit {
                 break;
@@ -678,6 +2684,17 @@ impl App {
     fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
         match self.mode {
             AppMode::View => {
+                // Answer to "delete all stale" confirmation
+                if self.pending_stale_delete {
+                    self.pending_stale_delete = false;
+                    if key.code == KeyCode::Char('y') {
+                        self.delete_stale_entries()?;
+                    } else {
+                        self.save_message = Some("Cancelled".to_string());
+                    }
+                    return Ok(());
+                }
+
                 // View mode keybindings (T035, T051)
                 match key.code {
                     KeyCode::Char('q') => {
@@ -686,6 +2703,15 @@ impl App {
                     KeyCode::Char('i') | KeyCode::Enter => {
                         self.enter_edit_mode()?;
                     }
+                    // Open a new line below / above
+                    KeyCode::Char('o') => {
+                        self.pending_delete = false;
+                        self.open_line(true)?;
+                    }
+                    KeyCode::Char('O') => {
+                        self.pending_delete = false;
+                        self.open_line(false)?;
+                    }
                     KeyCode::Esc => {
                         // Clear save message and cancel pending delete on Esc in view mode
                         self.save_message = None;
@@ -702,28 +2728,44 @@ impl App {
                         self.scroll_down()?;
                     }
                     // Navigation: page up
-                    KeyCode::Char('u') | KeyCode::PageUp => {
+                    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                         self.pending_delete = false;
                         self.page_up()?;
                     }
-                    // Navigation: page down / Delete line (dd)
-                    KeyCode::Char('d') | KeyCode::PageDown => {
-                        if key.code == KeyCode::Char('d') {
-                            if self.pending_delete {
-                                // Second 'd' press - execute deletion
-                                self.delete_current_line()?;
-                                self.pending_delete = false;
-                            } else {
-                                // First 'd' press - set pending state
-                                self.pending_delete = true;
-                                self.save_message = Some("Press 'd' again to delete line".to_string());
-                            }
-                        } else {
-                            // PageDown key
+                    KeyCode::PageUp => {
+                        self.pending_delete = false;
+                        self.page_up()?;
+                    }
+                    // Navigation: page down
+                    KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.pending_delete = false;
+                        self.page_down()?;
+                    }
+                    KeyCode::PageDown => {
+                        self.pending_delete = false;
+                        self.page_down()?;
+                    }
+                    // Delete line (dd)
+                    KeyCode::Char('d') => {
+                        if self.pending_delete {
+                            // Second 'd' press - execute deletion
+                            self.delete_current_line()?;
                             self.pending_delete = false;
-                            self.page_down()?;
+                        } else {
+                            // First 'd' press - set pending state
+                            self.pending_delete = true;
+                            self.save_message = Some("Press 'd' again to delete line".to_string());
                         }
                     }
+                    // Undo / redo
+                    KeyCode::Char('u') => {
+                        self.pending_delete = false;
+                        self.undo()?;
+                    }
+                    KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.pending_delete = false;
+                        self.redo()?;
+                    }
                     // Navigation: jump to top
                     KeyCode::Char('g') | KeyCode::Home => {
                         self.pending_delete = false;
@@ -747,12 +2789,121 @@ impl App {
                         self.delete_current_line()?;
                         self.pending_delete = false;
                     }
+                    // Search
+                    KeyCode::Char('/') => {
+                        self.pending_delete = false;
+                        self.enter_search_mode();
+                    }
+                    KeyCode::Char('n') => {
+                        self.pending_delete = false;
+                        self.search_next(true)?;
+                    }
+                    KeyCode::Char('N') => {
+                        self.pending_delete = false;
+                        self.search_next(false)?;
+                    }
+                    // Field filter
+                    KeyCode::Char('f') => {
+                        self.pending_delete = false;
+                        self.enter_filter_mode();
+                    }
+                    KeyCode::Char('F') => {
+                        self.pending_delete = false;
+                        self.set_filter(None)?;
+                    }
+                    // Stale entries: validate / delete all
+                    KeyCode::Char('s') => {
+                        self.pending_delete = false;
+                        self.validate_stale();
+                    }
+                    KeyCode::Char('S') => {
+                        self.pending_delete = false;
+                        self.request_stale_delete();
+                    }
+                    // Remove duplicate fingerprints
+                    KeyCode::Char('D') => {
+                        self.pending_delete = false;
+                        self.dedupe()?;
+                    }
+                    // Visual-line selection / paste yanked lines
+                    KeyCode::Char('V') => {
+                        self.pending_delete = false;
+                        self.enter_visual_mode();
+                    }
+                    KeyCode::Char('P') => {
+                        self.pending_delete = false;
+                        self.paste_register()?;
+                    }
+                    // Open report picker
+                    KeyCode::Char('r') => {
+                        self.pending_delete = false;
+                        self.enter_report_mode();
+                    }
+                    // Preview pane: focus, context and width
+                    KeyCode::Tab => {
+                        self.pending_delete = false;
+                        self.enter_preview_mode();
+                    }
+                    KeyCode::Char(c @ ('+' | '-')) => {
+                        self.pending_delete = false;
+                        self.view_state.resize_preview_context(c == '+');
+                        self.save_message = Some(self.preview_context_status());
+                    }
+                    KeyCode::Char(c @ ('<' | '>')) => {
+                        self.pending_delete = false;
+                        self.view_state.resize_preview_width(c == '<');
+                    }
+                    KeyCode::Char('R') => {
+                        self.pending_delete = false;
+                        self.toggle_reveal();
+                    }
+                    // Backup browser
+                    KeyCode::Char('b') => {
+                        self.pending_delete = false;
+                        self.enter_backup_mode()?;
+                    }
                     _ => {
                         // Any other key cancels pending delete
                         self.pending_delete = false;
                     }
                 }
             }
+            AppMode::Preview => {
+                // Preview pane keybindings
+                let half_page = (self.view_state.viewport_height / 2).max(1) as isize;
+                match key.code {
+                    KeyCode::Tab | KeyCode::Esc | KeyCode::Char('q') => {
+                        self.mode = AppMode::View;
+                        self.save_message = None;
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => self.view_state.scroll_preview(1),
+                    KeyCode::Char('k') | KeyCode::Up => self.view_state.scroll_preview(-1),
+                    KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.view_state.scroll_preview(half_page);
+                    }
+                    KeyCode::PageDown => self.view_state.scroll_preview(half_page),
+                    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.view_state.scroll_preview(-half_page);
+                    }
+                    KeyCode::PageUp => self.view_state.scroll_preview(-half_page),
+                    KeyCode::Char('g') | KeyCode::Home => self.view_state.scroll_preview(isize::MIN),
+                    KeyCode::Char('G') | KeyCode::End => self.view_state.scroll_preview(isize::MAX),
+                    // Back to the line the fingerprint points at
+                    KeyCode::Char('t') => self.view_state.scroll_preview_to_target(),
+                    KeyCode::Char('/') => self.enter_preview_search_mode(),
+                    KeyCode::Char('n') => self.preview_search_next(true),
+                    KeyCode::Char('N') => self.preview_search_next(false),
+                    KeyCode::Char(c @ ('+' | '-')) => {
+                        self.view_state.resize_preview_context(c == '+');
+                        self.save_message = Some(self.preview_context_status());
+                    }
+                    KeyCode::Char(c @ ('<' | '>')) => {
+                        self.view_state.resize_preview_width(c == '<');
+                    }
+                    KeyCode::Char('R') => self.toggle_reveal(),
+                    _ => {}
+                }
+            }
             AppMode::Edit => {
                 // Edit mode keybindings (T036)
                 match key.code {
@@ -762,7 +2913,7 @@ impl App {
                     }
                     KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                         // Cancel edit
-                        self.cancel_edit();
+                        self.cancel_edit()?;
                     }
                     _ => {
                         // Pass all other keys to textarea
@@ -772,8 +2923,371 @@ impl App {
                     }
                 }
             }
+            AppMode::Search => {
+                // Search prompt keybindings
+                match key.code {
+                    KeyCode::Enter => {
+                        self.confirm_search();
+                    }
+                    KeyCode::Esc => {
+                        self.cancel_search()?;
+                    }
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.cancel_search()?;
+                    }
+                    // Toggle case sensitivity
+                    KeyCode::Tab => {
+                        if let Some(ref mut search_state) = self.search_state {
+                            search_state.case_insensitive = !search_state.case_insensitive;
+                        }
+                        self.update_incremental_search()?;
+                    }
+                    // Toggle regex mode
+                    KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        if let Some(ref mut search_state) = self.search_state {
+                            search_state.regex = !search_state.regex;
+                        }
+                        self.update_incremental_search()?;
+                    }
+                    _ => {
+                        // Pass all other keys to the prompt
+                        if let Some(ref mut search_state) = self.search_state {
+                            search_state.textarea.input(key);
+                        }
+                        self.update_incremental_search()?;
+                    }
+                }
+            }
+            AppMode::Filter => {
+                // Filter prompt keybindings
+                match key.code {
+                    KeyCode::Enter => {
+                        self.confirm_filter()?;
+                    }
+                    KeyCode::Esc => {
+                        self.filter_state = None;
+                        self.mode = AppMode::View;
+                    }
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.filter_state = None;
+                        self.mode = AppMode::View;
+                    }
+                    _ => {
+                        // Pass all other keys to the prompt
+                        if let Some(ref mut filter_state) = self.filter_state {
+                            filter_state.textarea.input(key);
+                            filter_state.error = None;
+                        }
+                    }
+                }
+            }
+            AppMode::Visual => {
+                // Visual-line mode keybindings
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('V') | KeyCode::Char('q') => {
+                        self.exit_visual_mode();
+                    }
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.exit_visual_mode();
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        self.scroll_up()?;
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        self.scroll_down()?;
+                    }
+                    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.page_up()?;
+                    }
+                    KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.page_down()?;
+                    }
+                    KeyCode::PageUp => {
+                        self.page_up()?;
+                    }
+                    KeyCode::PageDown => {
+                        self.page_down()?;
+                    }
+                    KeyCode::Char('g') | KeyCode::Home => {
+                        self.jump_to_top()?;
+                    }
+                    KeyCode::Char('G') | KeyCode::End => {
+                        self.jump_to_bottom()?;
+                    }
+                    // Bulk operations on the selection
+                    KeyCode::Char('d') | KeyCode::Char('x') | KeyCode::Delete => {
+                        self.delete_selection()?;
+                    }
+                    KeyCode::Char('c') | KeyCode::Char('#') => {
+                        self.toggle_comment_selection()?;
+                    }
+                    KeyCode::Char('y') => {
+                        self.yank_selection();
+                    }
+                    _ => {}
+                }
+            }
+            AppMode::Conflict => {
+                // Conflict view keybindings
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        self.conflict_cancel()?;
+                    }
+                    KeyCode::Char('r') => {
+                        self.conflict_reload()?;
+                    }
+                    KeyCode::Char('o') => {
+                        self.conflict_overwrite()?;
+                    }
+                    KeyCode::Char('m') => {
+                        self.conflict_merge()?;
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        if let Some(ref mut conflict_state) = self.conflict_state {
+                            conflict_state.scroll = conflict_state.scroll.saturating_sub(1);
+                        }
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        if let Some(ref mut conflict_state) = self.conflict_state {
+                            if conflict_state.scroll + 1 < conflict_state.hunks.len() {
+                                conflict_state.scroll += 1;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            AppMode::Backups => {
+                // Backup browser keybindings
+                let half_page = (self.view_state.viewport_height / 2).max(1) as isize;
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        self.backup_state = None;
+                        self.mode = AppMode::View;
+                        self.save_message = None;
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => self.move_backup_cursor(-1),
+                    KeyCode::Char('j') | KeyCode::Down => self.move_backup_cursor(1),
+                    KeyCode::Char('g') | KeyCode::Home => self.move_backup_cursor(isize::MIN),
+                    KeyCode::Char('G') | KeyCode::End => self.move_backup_cursor(isize::MAX),
+                    // Scroll the diff
+                    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.scroll_backup_diff(-half_page);
+                    }
+                    KeyCode::PageUp => self.scroll_backup_diff(-half_page),
+                    KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.scroll_backup_diff(half_page);
+                    }
+                    KeyCode::PageDown => self.scroll_backup_diff(half_page),
+                    KeyCode::Enter => {
+                        self.restore_selected_backup()?;
+                    }
+                    _ => {}
+                }
+            }
+            AppMode::Report => {
+                // Report picker keybindings
+                let Some(ref mut report_state) = self.report_state else {
+                    self.mode = AppMode::View;
+                    return Ok(());
+                };
+
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => {
+                        self.mode = AppMode::View;
+                        self.save_message = None;
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        report_state.cursor = report_state.cursor.saturating_sub(1);
+                    }
+                    KeyCode::Char('j') | KeyCode::Down
+                        if report_state.cursor + 1 < report_state.findings.len() =>
+                    {
+                        report_state.cursor += 1;
+                    }
+                    KeyCode::Char('g') | KeyCode::Home => {
+                        report_state.cursor = 0;
+                    }
+                    KeyCode::Char('G') | KeyCode::End => {
+                        report_state.cursor = report_state.findings.len().saturating_sub(1);
+                    }
+                    KeyCode::Char(' ') => {
+                        report_state.toggle_current();
+                        if report_state.cursor + 1 < report_state.findings.len() {
+                            report_state.cursor += 1;
+                        }
+                    }
+                    KeyCode::Char('a') => {
+                        report_state.toggle_all();
+                    }
+                    KeyCode::Enter => {
+                        self.append_selected_findings()?;
+                    }
+                    _ => {}
+                }
+            }
         }
 
         Ok(())
     }
 }
+
+/// Key a fingerprint is compared by when checking whether it is already ignored
+///
+/// The [`DuplicateIndex`] normal form for fingerprints; other text is only trimmed.
+fn fingerprint_key(text: &str) -> String {
+    DuplicateIndex::normalize(&PatternType::parse(text))
+        .unwrap_or_else(|| text.trim().to_string())
+}
+
+/// [`fingerprint_key`] of a line of the ignore file
+fn line_key(line: &Line) -> String {
+    DuplicateIndex::normalize(line.pattern_type())
+        .unwrap_or_else(|| line.content().trim().to_string())
+}
+
+/// How long ago `time` was, e.g. "42s ago" or "3m ago"
+fn elapsed_label(time: SystemTime) -> String {
+    let secs = SystemTime::now()
+        .duration_since(time)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        _ => format!("{}h ago", secs / 3600),
+    }
+}
+
+/// Remove the leading `#` (and one following space) from a comment line, keeping the
+/// indentation before it
+fn uncomment_line(content: &str) -> String {
+    let trimmed = content.trim_start();
+    let indent = &content[..content.len() - trimmed.len()];
+    let rest = trimmed.strip_prefix('#').unwrap_or(trimmed);
+    format!("{}{}", indent, rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn elapsed_label_units() {
+        let now = SystemTime::now();
+        assert_eq!(elapsed_label(now), "0s ago");
+        assert_eq!(elapsed_label(now - Duration::from_secs(125)), "2m ago");
+        assert_eq!(elapsed_label(now - Duration::from_secs(7300)), "2h ago");
+    }
+
+    #[test]
+    fn failed_undo_leaves_no_backup() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        std::fs::write(&path, "a\n").unwrap();
+        let mut config = Config::default();
+        config.backup.dir = Some(PathBuf::from("backups"));
+        let mut app = App::new(path.clone(), crate::LineSpec::All, false, None, config).unwrap();
+
+        // Line 1 is not the "y" this change left, so reverting it fails
+        app.history.record(Change::new(
+            "edit",
+            vec![EditOperation::update(1, "x".into(), "y".into())],
+        ));
+        app.apply_history(true).unwrap();
+
+        assert!(app.save_message.unwrap().starts_with("Cannot undo"));
+        assert!(app.backup_manager.list_backups(&path).unwrap().is_empty());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\n");
+    }
+
+    #[test]
+    fn case_variant_fingerprints_count_as_ignored() {
+        let hash = "cd5226711335c68be1e720b318b7bc3135a30eb2";
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        std::fs::write(&path, format!("{}:src/a.go:rule:1\n", hash.to_ascii_uppercase())).unwrap();
+        let report = dir.path().join("report.json");
+        let findings: Vec<String> = [&format!("{}:src/a.go:rule:1", hash), "src/b.go:rule:2"]
+            .iter()
+            .map(|fingerprint| {
+                format!(r#"{{"RuleID": "rule", "File": "a", "Fingerprint": "{}"}}"#, fingerprint)
+            })
+            .collect();
+        std::fs::write(&report, format!("[{}]", findings.join(","))).unwrap();
+
+        let mut config = Config::default();
+        config.backup.dir = Some(PathBuf::from("backups"));
+        let mut app =
+            App::new(path.clone(), crate::LineSpec::All, false, Some(report), config).unwrap();
+        let report_state = app.report_state.as_mut().unwrap();
+        assert_eq!(report_state.ignored, [true, false]);
+
+        // Selecting everything only appends the entry that is not ignored yet
+        report_state.selected = vec![true, true];
+        app.append_selected_findings().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format!("{}:src/a.go:rule:1\nsrc/b.go:rule:2\n", hash.to_ascii_uppercase())
+        );
+    }
+
+    /// App viewing an ignore file with `lines`, with the cursor on `current_line`
+    fn app_at(dir: &tempfile::TempDir, lines: &[&str], current_line: usize) -> App {
+        let path = dir.path().join(".gitleaksignore");
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+        let mut config = Config::default();
+        config.backup.dir = Some(PathBuf::from("backups"));
+        let mut app = App::new(path, crate::LineSpec::All, false, None, config).unwrap();
+        app.refresh_view(current_line).unwrap();
+        app
+    }
+
+    /// Reload `app` from a file with `lines`, returning the entry under the cursor
+    fn reload_with(app: &mut App, lines: &[&str]) -> (usize, String) {
+        let path = app.view_state.file_context.file_path.clone();
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+        app.reload(FileContext::load_lossy(&path).unwrap()).unwrap();
+        let current_line = app.view_state.current_line;
+        let content = app.view_state.file_context.get_line(current_line).unwrap().content();
+        (current_line, content.to_string())
+    }
+
+    #[test]
+    fn reload_keeps_cursor_on_its_entry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = app_at(&dir, &["a:r:1", "b:r:1", "c:r:1", "d:r:1"], 3);
+
+        // Lines added and removed above the cursor
+        let cursor = reload_with(&mut app, &["x:r:1", "y:r:1", "a:r:1", "c:r:1", "d:r:1"]);
+        assert_eq!(cursor, (4, "c:r:1".to_string()));
+        assert!(app.history.next_undo().is_none());
+    }
+
+    #[test]
+    fn reload_moves_cursor_past_a_removed_entry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = app_at(&dir, &["a:r:1", "b:r:1", "c:r:1", "d:r:1"], 2);
+
+        let cursor = reload_with(&mut app, &["a:r:1", "c:r:1", "d:r:1"]);
+        assert_eq!(cursor, (2, "c:r:1".to_string()));
+
+        // Removing the last line leaves the cursor on the new last line
+        let mut app = app_at(&dir, &["a:r:1", "b:r:1"], 2);
+        let cursor = reload_with(&mut app, &["a:r:1"]);
+        assert_eq!(cursor, (1, "a:r:1".to_string()));
+    }
+
+    #[test]
+    fn uncomment_keeps_indentation() {
+        assert_eq!(uncomment_line("  # src/a.go:rule:1"), "  src/a.go:rule:1");
+        assert_eq!(uncomment_line("\t#src/a.go:rule:1"), "\tsrc/a.go:rule:1");
+        assert_eq!(uncomment_line("#  two spaces"), " two spaces");
+    }
+
+    #[test]
+    fn comment_toggle_restores_indented_line() {
+        let line = "    src/a.go:rule:1";
+        assert_eq!(uncomment_line(&format!("# {}", line)), line);
+    }
+}
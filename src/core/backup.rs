@@ -1,125 +1,298 @@
 /// Backup file handling
-use crate::error::Result;
-use std::fs;
+use crate::core::file_reader::FileContext;
+use crate::core::git::GitRepository;
+use crate::core::history::Change;
+use crate::error::{GliError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempfile::NamedTempFile;
+
+/// Name of the manifest in the backup directory
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Lock file serializing manifest updates between editor instances
+const LOCK_NAME: &str = "manifest.lock";
+
+/// Backup directory (next to the file) used outside a git repository
+const FALLBACK_DIR: &str = ".gli-editor-backups";
+
+/// Where backups are stored and how long they are kept
+#[derive(Debug, Clone)]
+pub struct BackupOptions {
+    /// Backup directory; relative paths are relative to the backed-up file's directory
+    ///
+    /// Defaults to `.git/gli-editor/backups` inside a git repository and to
+    /// `.gli-editor-backups` next to the file otherwise.
+    pub dir: Option<PathBuf>,
+    /// Number of backups kept per file (0 keeps all)
+    pub max_backups: usize,
+    /// Backups older than this are removed (None keeps them regardless of age)
+    pub max_age: Option<Duration>,
+}
+
+impl Default for BackupOptions {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            max_backups: 5,
+            max_age: None,
+        }
+    }
+}
+
+/// A backup recorded in the manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    /// Unique id within the backup directory
+    pub id: String,
+    /// Name of the backup file in the backup directory
+    pub file_name: String,
+    /// Absolute path of the backed-up file
+    pub source: PathBuf,
+    /// Creation time in milliseconds since the Unix epoch
+    pub created_ms: u64,
+    /// The change that was about to be written when the backup was made
+    pub operation: String,
+}
+
+impl BackupEntry {
+    /// Creation time
+    pub fn created(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.created_ms)
+    }
+
+    /// Creation time as `YYYY-MM-DD HH:MM:SS +0000` (UTC)
+    pub fn timestamp(&self) -> String {
+        let secs = self.created_ms / 1000;
+        let (year, month, day) = civil_from_days(secs / 86_400);
+        let time = secs % 86_400;
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02} +0000",
+            year,
+            month,
+            day,
+            time / 3600,
+            time / 60 % 60,
+            time % 60
+        )
+    }
+}
+
+/// Gregorian `(year, month, day)` of a day counted from 1970-01-01
+///
+/// Howard Hinnant's `civil_from_days`, restricted to dates after the epoch.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Shift the epoch to 0000-03-01 so leap days fall at the end of each year
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153; // March = 0
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    (year, month, day)
+}
 
 pub struct BackupManager {
-    /// Maximum number of backups to keep
-    max_backups: usize,
+    options: BackupOptions,
 }
 
 impl BackupManager {
-    /// Create a new BackupManager
+    /// Create a new BackupManager with the default options
     pub fn new() -> Self {
-        Self { max_backups: 5 }
+        Self::with_options(BackupOptions::default())
+    }
+
+    /// Create a BackupManager with custom location and retention
+    pub fn with_options(options: BackupOptions) -> Self {
+        Self { options }
     }
 
-    /// Create a timestamped backup of a file
+    /// Directory the backups of `file_path` are stored in
+    pub fn backup_dir<P: AsRef<Path>>(&self, file_path: P) -> PathBuf {
+        let abs_path = absolute(file_path.as_ref());
+        let parent = abs_path.parent().unwrap_or_else(|| Path::new("."));
+
+        match self.options.dir {
+            Some(ref dir) => parent.join(dir),
+            None => match GitRepository::discover(&abs_path) {
+                Some(repository) => repository.git_dir().join("gli-editor").join("backups"),
+                None => parent.join(FALLBACK_DIR),
+            },
+        }
+    }
+
+    /// Back up a file before `operation` (a short description of the change) modifies it
     ///
-    /// Returns the path to the created backup file
-    pub fn create_backup<P: AsRef<Path>>(&self, file_path: P) -> Result<PathBuf> {
-        let path = file_path.as_ref();
-
-        // Get absolute path to avoid path resolution issues
-        let abs_path = if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            std::env::current_dir()
-                .map(|cwd| cwd.join(path))
-                .unwrap_or_else(|_| path.to_path_buf())
-        };
+    /// Returns the path to the created backup file, or an empty path if the file does
+    /// not exist yet.
+    pub fn create_backup<P: AsRef<Path>>(&self, file_path: P, operation: &str) -> Result<PathBuf> {
+        let abs_path = absolute(file_path.as_ref());
 
         if !abs_path.exists() {
             // No need to backup if file doesn't exist
             return Ok(PathBuf::new());
         }
 
-        // Generate timestamp for backup filename
-        let timestamp = SystemTime::now()
+        let dir = self.backup_dir(&abs_path);
+        fs::create_dir_all(&dir)?;
+        let _lock = lock_manifest(&dir)?;
+        let mut entries = read_manifest(&dir)?;
+
+        let created_ms = SystemTime::now()
             .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let source_name = abs_path
+            .file_name()
+            .map_or_else(|| "file".into(), |name| name.to_string_lossy());
+
+        // The id is the creation time, suffixed with a counter if it is already taken
+        // (by an entry in the manifest or a file another process just created)
+        let mut attempt = 0;
+        let (id, file_name, mut file) = loop {
+            let id = match attempt {
+                0 => created_ms.to_string(),
+                n => format!("{}-{}", created_ms, n),
+            };
+            attempt += 1;
+            if entries.iter().any(|entry| entry.id == id) {
+                continue;
+            }
 
-        // Create backup filename: .gitleaksignore.backup.{timestamp}
-        let backup_path = abs_path.with_extension(format!("backup.{}", timestamp));
+            let file_name = format!("{}_{}", id, source_name);
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(dir.join(&file_name))
+            {
+                Ok(file) => break (id, file_name, file),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e.into()),
+            }
+        };
 
-        // Copy file to backup location
-        fs::copy(&abs_path, &backup_path)?;
+        // Copy the bytes as they are on disk (encoding and line endings included)
+        file.write_all(&fs::read(&abs_path)?)?;
+        file.sync_all()?;
 
-        // Clean up old backups
-        self.cleanup_old_backups(&abs_path)?;
+        entries.push(BackupEntry {
+            id,
+            file_name: file_name.clone(),
+            source: abs_path.clone(),
+            created_ms,
+            operation: operation.to_string(),
+        });
+        self.prune(&dir, &mut entries, &abs_path);
+        write_manifest(&dir, &entries)?;
 
-        Ok(backup_path)
+        Ok(dir.join(file_name))
     }
 
-    /// Remove old backups, keeping only the last max_backups files
-    pub fn cleanup_old_backups<P: AsRef<Path>>(&self, file_path: P) -> Result<()> {
-        let path = file_path.as_ref();
-
-        // Get absolute path to avoid path resolution issues
-        let abs_path = if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            std::env::current_dir()
-                .map(|cwd| cwd.join(path))
-                .unwrap_or_else(|_| path.to_path_buf())
-        };
+    /// Backups of a file, newest first
+    pub fn list_backups<P: AsRef<Path>>(&self, file_path: P) -> Result<Vec<BackupEntry>> {
+        let abs_path = absolute(file_path.as_ref());
+        let dir = self.backup_dir(&abs_path);
 
-        let parent = abs_path.parent().unwrap_or_else(|| Path::new("."));
-        let filename = abs_path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+        let mut entries: Vec<BackupEntry> = read_manifest(&dir)?
+            .into_iter()
+            .filter(|entry| entry.source == abs_path && dir.join(&entry.file_name).exists())
+            .collect();
+        entries.sort_by(|a, b| (b.created_ms, &b.id).cmp(&(a.created_ms, &a.id)));
+        Ok(entries)
+    }
 
-        // Verify parent directory exists and is readable
-        if !parent.exists() {
-            // Parent directory doesn't exist, skip cleanup (no backups to clean)
-            return Ok(());
-        }
+    /// The backup of a file with the given id
+    pub fn find_backup<P: AsRef<Path>>(&self, file_path: P, id: &str) -> Result<BackupEntry> {
+        self.list_backups(file_path)?
+            .into_iter()
+            .find(|entry| entry.id == id)
+            .ok_or_else(|| GliError::BackupNotFound(id.to_string()))
+    }
 
-        // Find all backup files
-        let mut backups = Vec::new();
+    /// Path of a backup file
+    pub fn backup_path<P: AsRef<Path>>(&self, file_path: P, entry: &BackupEntry) -> PathBuf {
+        self.backup_dir(file_path).join(&entry.file_name)
+    }
 
-        // Use pattern matching to handle read_dir errors gracefully
-        let dir_entries = match fs::read_dir(parent) {
-            Ok(entries) => entries,
-            Err(_) => {
-                // If we can't read the directory, skip cleanup
-                // This is not a critical error - backups just won't be cleaned up
-                return Ok(());
-            }
-        };
+    /// Load the content of a backup
+    pub fn load_backup<P: AsRef<Path>>(
+        &self,
+        file_path: P,
+        entry: &BackupEntry,
+    ) -> Result<FileContext> {
+        FileContext::load_lossy(self.backup_path(file_path, entry))
+    }
 
-        for entry in dir_entries {
-            let entry = match entry {
-                Ok(e) => e,
-                Err(_) => continue, // Skip entries we can't read
-            };
+    /// Change that restores the content of a backup into `file_context`
+    ///
+    /// Applying it and writing the file goes through the usual (backed-up, atomic) write
+    /// path; the change is empty if the file already matches the backup.
+    pub fn restore_change(
+        &self,
+        file_context: &FileContext,
+        entry: &BackupEntry,
+    ) -> Result<Change> {
+        let backup = self.load_backup(&file_context.file_path, entry)?;
+        Ok(Change::between(
+            format!("restore backup {}", entry.id),
+            file_context,
+            &backup,
+        ))
+    }
 
-            let entry_path = entry.path();
+    /// Remove backups of a file beyond the configured count and age
+    pub fn cleanup_old_backups<P: AsRef<Path>>(&self, file_path: P) -> Result<()> {
+        let abs_path = absolute(file_path.as_ref());
+        let dir = self.backup_dir(&abs_path);
 
-            if let Some(entry_name) = entry_path.file_name().and_then(|s| s.to_str()) {
-                // Check if this is a backup file for our target file
-                if entry_name.starts_with(filename) && entry_name.contains(".backup.") {
-                    if let Ok(metadata) = entry.metadata() {
-                        if let Ok(modified) = metadata.modified() {
-                            backups.push((entry_path, modified));
-                        }
-                    }
-                }
-            }
+        if !dir.join(MANIFEST_NAME).exists() {
+            // No backups to clean
+            return Ok(());
         }
 
-        // Sort backups by modification time (oldest first)
-        backups.sort_by_key(|(_, time)| *time);
+        let _lock = lock_manifest(&dir)?;
+        let mut entries = read_manifest(&dir)?;
+        self.prune(&dir, &mut entries, &abs_path);
+        write_manifest(&dir, &entries)
+    }
+
+    /// Drop the backups of `source` that exceed the retention limits, and manifest
+    /// entries whose backup file no longer exists
+    fn prune(&self, dir: &Path, entries: &mut Vec<BackupEntry>, source: &Path) {
+        let now = SystemTime::now();
+        let mut kept = 0;
 
-        // Remove old backups if we exceed max_backups
-        if backups.len() > self.max_backups {
-            let to_remove = backups.len() - self.max_backups;
-            for (backup_path, _) in backups.iter().take(to_remove) {
-                let _ = fs::remove_file(backup_path); // Ignore errors on cleanup
+        // Newest first, so the count limit keeps the most recent backups
+        entries.sort_by(|a, b| (b.created_ms, &b.id).cmp(&(a.created_ms, &a.id)));
+        entries.retain(|entry| {
+            let path = dir.join(&entry.file_name);
+            if !path.exists() {
+                return false;
+            }
+            if entry.source != source {
+                return true;
             }
-        }
 
-        Ok(())
+            let too_many = self.options.max_backups > 0 && kept >= self.options.max_backups;
+            let too_old = self.options.max_age.is_some_and(|max_age| {
+                now.duration_since(entry.created())
+                    .is_ok_and(|age| age > max_age)
+            });
+            if too_many || too_old {
+                let _ = fs::remove_file(&path); // Ignore errors on cleanup
+                return false;
+            }
+
+            kept += 1;
+            true
+        });
+        entries.reverse();
     }
 }
 
@@ -128,3 +301,167 @@ impl Default for BackupManager {
         Self::new()
     }
 }
+
+/// Make a path absolute against the working directory (canonical if it exists), so the
+/// manifest refers to each file by a single path
+fn absolute(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
+
+/// Take an exclusive advisory lock on the manifest of a backup directory, held until the
+/// returned file is dropped
+///
+/// Updates read, modify and write the manifest; without the lock, two instances backing
+/// up at the same time would drop each other's entries.
+fn lock_manifest(dir: &Path) -> Result<File> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_NAME))?;
+    file.lock()?;
+    Ok(file)
+}
+
+/// Read the manifest of a backup directory (empty if there is none yet)
+fn read_manifest(dir: &Path) -> Result<Vec<BackupEntry>> {
+    let path = dir.join(MANIFEST_NAME);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    serde_json::from_str(&content).map_err(|e| {
+        GliError::WriteFailure(format!("corrupt backup manifest {}: {}", path.display(), e))
+    })
+}
+
+/// Replace the manifest of a backup directory atomically
+fn write_manifest(dir: &Path, entries: &[BackupEntry]) -> Result<()> {
+    let json = serde_json::to_string_pretty(entries)
+        .map_err(|e| GliError::WriteFailure(format!("backup manifest: {}", e)))?;
+
+    let mut temp = NamedTempFile::new_in(dir)?;
+    temp.write_all(json.as_bytes())?;
+    temp.write_all(b"\n")?;
+    temp.as_file().sync_all()?;
+    temp.persist(dir.join(MANIFEST_NAME))
+        .map_err(|e| GliError::WriteFailure(format!("backup manifest: {}", e.error)))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use tempfile::TempDir;
+
+    #[test]
+    fn restore_change_round_trips() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join(".gitleaksignore");
+        let manager = BackupManager::with_options(BackupOptions {
+            dir: Some(PathBuf::from("backups")),
+            max_backups: 0,
+            max_age: None,
+        });
+        let lines = |file_context: &FileContext| -> Vec<String> {
+            file_context.lines.iter().map(|line| line.content().to_string()).collect()
+        };
+
+        // (backed-up content, content on disk when restoring)
+        let cases = [
+            ("a.go:rule:1\nb.go:rule:2\n", "b.go:rule:2\nc.go:rule:3\n"),
+            ("a.go:rule:1\n", "x.go:rule:9\ny.go:rule:8\n"),
+            ("", "a.go:rule:1\n"),
+            ("a.go:rule:1\n", ""),
+        ];
+        for (backed_up, current) in cases {
+            fs::write(&file, backed_up).unwrap();
+            let backup = manager.create_backup(&file, "edit").unwrap();
+            let entry = manager
+                .list_backups(&file)
+                .unwrap()
+                .into_iter()
+                .find(|entry| manager.backup_path(&file, entry) == backup)
+                .unwrap();
+            fs::write(&file, current).unwrap();
+
+            let mut file_context = FileContext::load(&file).unwrap();
+            let change = manager.restore_change(&file_context, &entry).unwrap();
+            change.apply(&mut file_context).unwrap();
+            file_context.write_atomic().unwrap();
+            assert_eq!(fs::read_to_string(&file).unwrap(), backed_up);
+
+            change.inverse("undo restore").apply(&mut file_context).unwrap();
+            assert_eq!(lines(&file_context), current.lines().collect::<Vec<_>>());
+
+            // Restoring a backup the file already matches changes nothing
+            let restored = FileContext::load(&file).unwrap();
+            assert!(manager.restore_change(&restored, &entry).unwrap().operations.is_empty());
+        }
+    }
+
+    #[test]
+    fn concurrent_backups_keep_every_manifest_entry() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join(".gitleaksignore");
+        fs::write(&file, "a.go:rule:1\n").unwrap();
+
+        let options = BackupOptions {
+            dir: Some(PathBuf::from("backups")),
+            max_backups: 0,
+            max_age: None,
+        };
+        let workers: Vec<_> = (0..4)
+            .map(|worker| {
+                let (file, options) = (file.clone(), options.clone());
+                thread::spawn(move || {
+                    // A manager per thread, like separate editor instances
+                    let manager = BackupManager::with_options(options);
+                    for n in 0..5 {
+                        manager.create_backup(&file, &format!("edit {}-{}", worker, n)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        let backups = BackupManager::with_options(options).list_backups(&file).unwrap();
+        assert_eq!(backups.len(), 20);
+    }
+
+    #[test]
+    fn timestamp_is_utc() {
+        let entry = BackupEntry {
+            id: "0".to_string(),
+            file_name: "0_.gitleaksignore".to_string(),
+            source: PathBuf::from("/repo/.gitleaksignore"),
+            created_ms: 86_400_000 + 1_500,
+            operation: "edit line 1".to_string(),
+        };
+        assert_eq!(entry.timestamp(), "1970-01-02 00:00:01 +0000");
+
+        let at = |secs: u64| BackupEntry {
+            created_ms: secs * 1000,
+            ..entry.clone()
+        };
+        assert_eq!(at(0).timestamp(), "1970-01-01 00:00:00 +0000");
+        assert_eq!(at(951_782_400).timestamp(), "2000-02-29 00:00:00 +0000");
+        assert_eq!(at(1_700_000_000).timestamp(), "2023-11-14 22:13:20 +0000");
+        assert_eq!(at(4_107_542_399).timestamp(), "2100-02-28 23:59:59 +0000");
+    }
+}
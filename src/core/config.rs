@@ -0,0 +1,279 @@
+/// Layered configuration: user config file, repository config file, command-line flags
+use crate::core::backup::BackupOptions;
+use crate::core::git::GitRepository;
+use crate::core::stale;
+use crate::error::{GliError, Result};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// Name of the repository config file, looked up at the repository root
+pub const REPO_CONFIG_NAME: &str = ".gli-editor.toml";
+
+/// Seconds in a day, for `max_age_days`
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Settings from one or more layers; unset values fall back to the next layer down
+/// and finally to the built-in defaults
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Directory fingerprint paths are relative to (default: see [`stale::scan_root`])
+    pub root: Option<PathBuf>,
+    pub view: ViewConfig,
+    pub preview: PreviewConfig,
+    pub backup: BackupConfig,
+    pub colors: ColorConfig,
+}
+
+/// `[view]`: the file view
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ViewConfig {
+    /// Context lines around the line given with `--lines` (default: 3)
+    pub context: Option<usize>,
+    /// Lines kept visible above and below the cursor when scrolling (default: 3)
+    pub scroll_margin: Option<usize>,
+}
+
+/// `[preview]`: the preview pane
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreviewConfig {
+    /// Lines shown above and below the target line (default: 10)
+    pub context: Option<usize>,
+    /// Width of the pane in percent of the screen (default: 50)
+    pub width: Option<u16>,
+    /// Never reveal masked secrets; once enabled, later config layers cannot disable it
+    pub no_reveal: Option<bool>,
+}
+
+/// `[backup]`: backup location and retention (see [`BackupOptions`])
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupConfig {
+    pub dir: Option<PathBuf>,
+    /// Number of backups kept per file (0 keeps all)
+    pub keep: Option<usize>,
+    /// Remove backups older than this many days
+    pub max_age_days: Option<u64>,
+}
+
+/// `[colors]`: colors of the file view, as color names (`red`, `lightblue`), `#rrggbb`
+/// or 256-color indexes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorConfig {
+    pub commit_hash: Option<String>,
+    pub file_path: Option<String>,
+    pub rule_id: Option<String>,
+    pub line_number: Option<String>,
+    pub comment: Option<String>,
+    pub invalid: Option<String>,
+    pub stale: Option<String>,
+    /// Background of the current line
+    pub current_line: Option<String>,
+    /// Background of the visual-line selection
+    pub selection: Option<String>,
+}
+
+impl Config {
+    /// Load the user config and the repository config of `ignore_file`, the latter
+    /// taking precedence; missing files are skipped
+    ///
+    /// The repository config comes with the checkout, so its `root` and `backup.dir` must
+    /// stay inside the repository: otherwise a cloned repository could have backups of
+    /// the ignore file written (or source files previewed) anywhere.
+    pub fn load(ignore_file: &Path) -> Result<Self> {
+        let mut config = Config::default();
+        let repository = GitRepository::discover(ignore_file);
+        let repo_root = stale::scan_root(ignore_file, repository.as_ref());
+
+        if let Some(path) = user_config_path().filter(|path| path.is_file()) {
+            config = config.merge(Config::from_file(&path)?);
+        }
+
+        let repo_config = repo_root.join(REPO_CONFIG_NAME);
+        if repo_config.is_file() {
+            let layer = Config::from_file(&repo_config)?;
+            for (key, path) in [("root", &layer.root), ("backup.dir", &layer.backup.dir)] {
+                if path.as_ref().is_some_and(|path| !is_inside(path, &repo_root)) {
+                    return Err(GliError::InvalidConfig(format!(
+                        "{}: {} must be inside the repository; set it in the user config \
+                         or on the command line instead",
+                        repo_config.display(),
+                        key
+                    )));
+                }
+            }
+            config = config.merge(layer);
+        }
+
+        Ok(config)
+    }
+
+    /// Parse a config file; relative paths in it are relative to its directory
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let mut config: Config = toml::from_str(&content)
+            .map_err(|e| GliError::InvalidConfig(format!("{}: {}", path.display(), e)))?;
+
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+        config.root = config.root.map(|root| dir.join(root));
+        config.backup.dir = config.backup.dir.map(|backup_dir| dir.join(backup_dir));
+        Ok(config)
+    }
+
+    /// Layer `other` on top of this config: its settings win where they are set
+    ///
+    /// `preview.no_reveal` is the exception: it is sticky, so once a layer enables it a
+    /// later layer (e.g. the repository config) cannot turn it off again.
+    pub fn merge(self, other: Config) -> Config {
+        Config {
+            root: other.root.or(self.root),
+            view: ViewConfig {
+                context: other.view.context.or(self.view.context),
+                scroll_margin: other.view.scroll_margin.or(self.view.scroll_margin),
+            },
+            preview: PreviewConfig {
+                context: other.preview.context.or(self.preview.context),
+                width: other.preview.width.or(self.preview.width),
+                no_reveal: match (self.preview.no_reveal, other.preview.no_reveal) {
+                    (Some(base), Some(layer)) => Some(base || layer),
+                    (base, layer) => layer.or(base),
+                },
+            },
+            backup: BackupConfig {
+                dir: other.backup.dir.or(self.backup.dir),
+                keep: other.backup.keep.or(self.backup.keep),
+                max_age_days: other.backup.max_age_days.or(self.backup.max_age_days),
+            },
+            colors: ColorConfig {
+                commit_hash: other.colors.commit_hash.or(self.colors.commit_hash),
+                file_path: other.colors.file_path.or(self.colors.file_path),
+                rule_id: other.colors.rule_id.or(self.colors.rule_id),
+                line_number: other.colors.line_number.or(self.colors.line_number),
+                comment: other.colors.comment.or(self.colors.comment),
+                invalid: other.colors.invalid.or(self.colors.invalid),
+                stale: other.colors.stale.or(self.colors.stale),
+                current_line: other.colors.current_line.or(self.colors.current_line),
+                selection: other.colors.selection.or(self.colors.selection),
+            },
+        }
+    }
+}
+
+impl BackupConfig {
+    /// Backup options with unset values at their defaults
+    pub fn options(&self) -> BackupOptions {
+        let defaults = BackupOptions::default();
+        BackupOptions {
+            dir: self.dir.clone().or(defaults.dir),
+            max_backups: self.keep.unwrap_or(defaults.max_backups),
+            max_age: self
+                .max_age_days
+                .map(|days| Duration::from_secs(days.saturating_mul(SECS_PER_DAY)))
+                .or(defaults.max_age),
+        }
+    }
+}
+
+/// Whether `path` lies inside `dir`, following symlinks
+///
+/// `path` need not exist yet (e.g. a backup directory): its closest existing ancestor is
+/// checked, and `..` components are rejected since they may climb out of it.
+fn is_inside(path: &Path, dir: &Path) -> bool {
+    if path.components().any(|component| component == Component::ParentDir) {
+        return false;
+    }
+    let Ok(dir) = dir.canonicalize() else {
+        return false;
+    };
+    path.ancestors()
+        .find(|ancestor| ancestor.exists())
+        .and_then(|ancestor| ancestor.canonicalize().ok())
+        .is_some_and(|ancestor| ancestor.starts_with(&dir))
+}
+
+/// Path of the user config file: `$XDG_CONFIG_HOME/gli-editor/config.toml`, with
+/// `XDG_CONFIG_HOME` defaulting to `~/.config` (None if neither is set)
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join("gli-editor").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn no_reveal(value: Option<bool>) -> Config {
+        Config {
+            preview: PreviewConfig {
+                no_reveal: value,
+                ..PreviewConfig::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn later_layers_override_settings() {
+        let mut user = Config::default();
+        user.preview.context = Some(5);
+        user.backup.keep = Some(3);
+        let mut repo = Config::default();
+        repo.preview.context = Some(2);
+
+        let merged = user.merge(repo);
+        assert_eq!(merged.preview.context, Some(2));
+        assert_eq!(merged.backup.keep, Some(3));
+    }
+
+    #[test]
+    fn no_reveal_is_sticky() {
+        let merge = |base, layer| no_reveal(base).merge(no_reveal(layer)).preview.no_reveal;
+
+        assert_eq!(merge(Some(true), Some(false)), Some(true));
+        assert_eq!(merge(Some(false), Some(true)), Some(true));
+        assert_eq!(merge(Some(false), Some(false)), Some(false));
+        assert_eq!(merge(Some(true), None), Some(true));
+        assert_eq!(merge(None, Some(false)), Some(false));
+        assert_eq!(merge(None, None), None);
+    }
+
+    #[test]
+    fn paths_inside_the_directory_are_accepted() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+
+        assert!(is_inside(&dir.path().join(".git/gli-editor/backups"), dir.path()));
+        assert!(is_inside(&dir.path().join("."), dir.path()));
+    }
+
+    #[test]
+    fn paths_outside_the_directory_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+
+        assert!(!is_inside(&dir.path().join("../elsewhere"), dir.path()));
+        assert!(!is_inside(&dir.path().join("a/../../elsewhere"), dir.path()));
+        assert!(!is_inside(outside.path(), dir.path()));
+        assert!(!is_inside(Path::new("/tmp/backups"), dir.path()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_out_of_the_directory_are_rejected() {
+        let dir = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("backups")).unwrap();
+
+        assert!(!is_inside(&dir.path().join("backups/new"), dir.path()));
+    }
+}
@@ -0,0 +1,200 @@
+/// Line-based diff between two versions of a file
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Present in both versions
+    Equal,
+    /// Only in the old version
+    Removed,
+    /// Only in the new version
+    Added,
+}
+
+/// A single line of a diff
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    /// Line number in the old version (None for added lines)
+    pub old_line: Option<usize>,
+    /// Line number in the new version (None for removed lines)
+    pub new_line: Option<usize>,
+    pub content: String,
+}
+
+/// Maximum LCS table size; larger differing regions are treated as fully replaced
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Diff two versions of a file line by line
+pub fn diff_lines<S: AsRef<str>>(old: &[S], new: &[S]) -> Vec<DiffLine> {
+    let mut result = Vec::new();
+    let (mut old_idx, mut new_idx) = (0, 0);
+
+    let removed = |idx: usize| DiffLine {
+        kind: DiffKind::Removed,
+        old_line: Some(idx + 1),
+        new_line: None,
+        content: old[idx].as_ref().to_string(),
+    };
+    let added = |idx: usize| DiffLine {
+        kind: DiffKind::Added,
+        old_line: None,
+        new_line: Some(idx + 1),
+        content: new[idx].as_ref().to_string(),
+    };
+
+    for (old_match, new_match) in matching_pairs(old, new) {
+        result.extend((old_idx..old_match).map(removed));
+        result.extend((new_idx..new_match).map(added));
+        result.push(DiffLine {
+            kind: DiffKind::Equal,
+            old_line: Some(old_match + 1),
+            new_line: Some(new_match + 1),
+            content: old[old_match].as_ref().to_string(),
+        });
+        old_idx = old_match + 1;
+        new_idx = new_match + 1;
+    }
+    result.extend((old_idx..old.len()).map(removed));
+    result.extend((new_idx..new.len()).map(added));
+
+    result
+}
+
+/// Diff two versions of a file, keeping `context` unchanged lines around each difference
+///
+/// None marks skipped unchanged lines.
+pub fn diff_hunks<S: AsRef<str>>(old: &[S], new: &[S], context: usize) -> Vec<Option<DiffLine>> {
+    let diff = diff_lines(old, new);
+
+    let is_near_change = |idx: usize| {
+        let start = idx.saturating_sub(context);
+        let end = (idx + context + 1).min(diff.len());
+        diff[start..end].iter().any(|line| line.kind != DiffKind::Equal)
+    };
+    let keep: Vec<bool> = (0..diff.len()).map(is_near_change).collect();
+
+    let mut hunks = Vec::new();
+    for (line, keep) in diff.into_iter().zip(keep) {
+        if keep {
+            hunks.push(Some(line));
+        } else if !matches!(hunks.last(), Some(None)) {
+            hunks.push(None);
+        }
+    }
+
+    hunks
+}
+
+/// Map each old line to the new line holding the same, unchanged content
+///
+/// Index `i` holds the new line number for old line `i + 1`, or None if the line was
+/// removed or modified.
+pub fn line_mapping<S: AsRef<str>>(old: &[S], new: &[S]) -> Vec<Option<usize>> {
+    let mut mapping = vec![None; old.len()];
+    for (old_idx, new_idx) in matching_pairs(old, new) {
+        mapping[old_idx] = Some(new_idx + 1);
+    }
+    mapping
+}
+
+/// Indices (0-based, ascending) of lines common to both versions
+///
+/// The common prefix and suffix are matched directly so that the usual case of a few
+/// edited lines stays cheap; the remainder uses a longest common subsequence.
+fn matching_pairs<S: AsRef<str>>(old: &[S], new: &[S]) -> Vec<(usize, usize)> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(a, b)| a.as_ref() == b.as_ref())
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a.as_ref() == b.as_ref())
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|idx| (idx, idx)).collect();
+    if old_mid.len().saturating_mul(new_mid.len()) <= MAX_LCS_CELLS {
+        pairs.extend(
+            lcs_pairs(old_mid, new_mid)
+                .into_iter()
+                .map(|(o, n)| (o + prefix, n + prefix)),
+        );
+    }
+    pairs.extend((0..suffix).map(|idx| (old.len() - suffix + idx, new.len() - suffix + idx)));
+
+    pairs
+}
+
+/// Longest common subsequence of two line slices
+fn lcs_pairs<S: AsRef<str>>(old: &[S], new: &[S]) -> Vec<(usize, usize)> {
+    let (n, m) = (old.len(), new.len());
+    if n == 0 || m == 0 {
+        return Vec::new();
+    }
+
+    // lengths[i * (m + 1) + j] = LCS length of old[i..] and new[j..]
+    let width = m + 1;
+    let mut lengths = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i * width + j] = if old[i].as_ref() == new[j].as_ref() {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i].as_ref() == new[j].as_ref() {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_mapping_insert_at_line_one() {
+        assert_eq!(line_mapping(&["a", "b"], &["x", "a", "b"]), [Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn line_mapping_changed_line_is_unmapped() {
+        assert_eq!(
+            line_mapping(&["a", "b", "c"], &["a", "B", "c", "d"]),
+            [Some(1), None, Some(3)]
+        );
+    }
+
+    #[test]
+    fn line_mapping_deleted_last_line() {
+        assert_eq!(line_mapping(&["a", "b", "c"], &["a", "b"]), [Some(1), Some(2), None]);
+    }
+
+    #[test]
+    fn line_mapping_conflicting_edits() {
+        // Both sides replaced the middle line; only the untouched lines map
+        assert_eq!(
+            line_mapping(&["a", "b", "c"], &["a", "x", "y", "c"]),
+            [Some(1), None, Some(4)]
+        );
+    }
+}
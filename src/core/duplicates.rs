@@ -0,0 +1,273 @@
+/// Duplicate fingerprint detection
+use crate::models::fingerprint::Fingerprint;
+use crate::models::line::Line;
+use crate::models::pattern::PatternType;
+use imbl::Vector;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Index of fingerprints that appear more than once
+///
+/// Built once over a file and then kept up to date line by line as the file is edited
+/// (see [`FileContext::duplicates`](crate::core::file_reader::FileContext::duplicates)),
+/// so an edit only classifies the lines it touches.
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateIndex {
+    /// Number of lines per normalized fingerprint
+    counts: HashMap<String, usize>,
+    /// Line numbers (ascending) of every fingerprint that appears more than once
+    groups: HashMap<String, Vec<usize>>,
+    /// Line number -> normalized fingerprint, for every member of a duplicate group
+    members: BTreeMap<usize, String>,
+}
+
+impl DuplicateIndex {
+    /// Build the index over all lines of a file
+    pub fn build<'a>(lines: impl IntoIterator<Item = &'a Line>) -> Self {
+        let mut index = Self::default();
+        let mut first_seen: HashMap<String, usize> = HashMap::new();
+
+        for (idx, line) in lines.into_iter().enumerate() {
+            let line_number = idx + 1;
+            let Some(key) = Self::normalize(line.pattern_type()) else {
+                continue;
+            };
+
+            *index.counts.entry(key.clone()).or_default() += 1;
+            match first_seen.get(&key) {
+                Some(&first) => {
+                    index
+                        .groups
+                        .entry(key.clone())
+                        .or_insert_with(|| vec![first])
+                        .push(line_number);
+                    index.members.insert(first, key.clone());
+                    index.members.insert(line_number, key);
+                }
+                None => {
+                    first_seen.insert(key, line_number);
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Normalized form of a fingerprint used as the index key
+    ///
+    /// Surrounding whitespace is ignored and commit hashes compare case-insensitively.
+    /// Returns None for lines that are not fingerprints.
+    pub fn normalize(pattern_type: &PatternType) -> Option<String> {
+        let PatternType::Fingerprint(Fingerprint {
+            commit_hash,
+            file_path,
+            rule_id,
+            line_number,
+        }) = pattern_type
+        else {
+            return None;
+        };
+
+        Some(match commit_hash {
+            Some(hash) => format!(
+                "{}:{}:{}:{}",
+                hash.to_ascii_lowercase(),
+                file_path,
+                rule_id,
+                line_number
+            ),
+            None => format!("{}:{}:{}", file_path, rule_id, line_number),
+        })
+    }
+
+    /// First line with the same fingerprint, if `line_number` belongs to a duplicate group
+    pub fn first_occurrence(&self, line_number: usize) -> Option<usize> {
+        let key = self.members.get(&line_number)?;
+        self.groups.get(key).and_then(|group| group.first()).copied()
+    }
+
+    /// Whether `line_number` repeats a fingerprint seen on an earlier line
+    pub fn is_later_occurrence(&self, line_number: usize) -> bool {
+        self.first_occurrence(line_number)
+            .is_some_and(|first| first != line_number)
+    }
+
+    /// Line numbers of all later occurrences, in ascending order
+    pub fn later_occurrences(&self) -> Vec<usize> {
+        self.members
+            .keys()
+            .copied()
+            .filter(|&line_number| self.is_later_occurrence(line_number))
+            .collect()
+    }
+
+    /// Record `line`, which now sits at `line_number` in `lines`
+    ///
+    /// Lines after it must already have been moved with [`DuplicateIndex::shift`].
+    pub(crate) fn add(&mut self, line_number: usize, line: &Line, lines: &Vector<Line>) {
+        let Some(key) = Self::normalize(line.pattern_type()) else {
+            return;
+        };
+
+        let count = self.counts.entry(key.clone()).or_default();
+        *count += 1;
+        match *count {
+            1 => {}
+            // The other occurrence was only counted so far; look up where it is
+            2 => {
+                let other = lines.iter().enumerate().position(|(idx, candidate)| {
+                    idx + 1 != line_number
+                        && Self::normalize(candidate.pattern_type()).as_ref() == Some(&key)
+                });
+                if let Some(other) = other.map(|idx| idx + 1) {
+                    let mut group = vec![other, line_number];
+                    group.sort_unstable();
+                    self.members.insert(other, key.clone());
+                    self.members.insert(line_number, key.clone());
+                    self.groups.insert(key, group);
+                }
+            }
+            _ => {
+                if let Some(group) = self.groups.get_mut(&key) {
+                    let pos = group.partition_point(|&member| member < line_number);
+                    group.insert(pos, line_number);
+                    self.members.insert(line_number, key);
+                }
+            }
+        }
+    }
+
+    /// Forget `line`, which sits at `line_number`, without moving the lines after it
+    pub(crate) fn remove(&mut self, line_number: usize, line: &Line) {
+        let Some(key) = Self::normalize(line.pattern_type()) else {
+            return;
+        };
+
+        if let Some(count) = self.counts.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&key);
+            }
+        }
+
+        if let Some(group) = self.groups.get_mut(&key) {
+            group.retain(|&member| member != line_number);
+            self.members.remove(&line_number);
+            // A single remaining occurrence is no longer a duplicate
+            if group.len() < 2 {
+                for member in group.iter() {
+                    self.members.remove(member);
+                }
+                self.groups.remove(&key);
+            }
+        }
+    }
+
+    /// Move every line at or after `from` by `delta` lines
+    pub(crate) fn shift(&mut self, from: usize, delta: isize) {
+        let moved = self.members.split_off(&from);
+
+        let keys: HashSet<&String> = moved.values().collect();
+        for key in keys {
+            if let Some(group) = self.groups.get_mut(key) {
+                for member in group.iter_mut().filter(|member| **member >= from) {
+                    *member = member.saturating_add_signed(delta);
+                }
+            }
+        }
+
+        for (line_number, key) in moved {
+            self.members.insert(line_number.saturating_add_signed(delta), key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::file_reader::FileContext;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const A: &str = "src/a.go:aws-access-token:1";
+    const B: &str = "src/b.go:generic-api-key:2";
+    const C: &str = "cd5226711335c68be1e720b318b7bc3135a30eb2:src/c.go:jwt:3";
+
+    fn file(lines: &[&str]) -> FileContext {
+        let mut temp = NamedTempFile::new().unwrap();
+        for line in lines {
+            writeln!(temp, "{}", line).unwrap();
+        }
+        FileContext::load(temp.path()).unwrap()
+    }
+
+    /// The incrementally updated index agrees with one built from scratch
+    fn assert_matches_rebuild(file_context: &FileContext) {
+        let rebuilt = DuplicateIndex::build(&file_context.lines);
+        let index = file_context.duplicates();
+        assert_eq!(index.later_occurrences(), rebuilt.later_occurrences());
+        for line_number in 1..=file_context.total_lines {
+            assert_eq!(
+                index.first_occurrence(line_number),
+                rebuilt.first_occurrence(line_number),
+                "line {}",
+                line_number
+            );
+        }
+    }
+
+    #[test]
+    fn build_groups_duplicates() {
+        let upper = "CD5226711335C68BE1E720B318B7BC3135A30EB2:src/c.go:jwt:3";
+        let file_context = file(&[A, B, A, "# comment", upper, C, A]);
+        let index = file_context.duplicates();
+
+        assert_eq!(index.later_occurrences(), [3, 6, 7]);
+        assert_eq!(index.first_occurrence(7), Some(1));
+        assert_eq!(index.first_occurrence(6), Some(5));
+        assert_eq!(index.first_occurrence(2), None);
+    }
+
+    #[test]
+    fn insert_and_append_create_groups() {
+        let mut file_context = file(&[A, B, C]);
+        assert!(file_context.duplicates().later_occurrences().is_empty());
+
+        file_context.insert_line(1, B.to_string()).unwrap();
+        assert_matches_rebuild(&file_context);
+        assert_eq!(file_context.duplicates().first_occurrence(3), Some(1));
+
+        file_context.append_line(A.to_string());
+        file_context.insert_line(3, "# comment".to_string()).unwrap();
+        assert_matches_rebuild(&file_context);
+    }
+
+    #[test]
+    fn delete_shifts_and_dissolves_groups() {
+        let mut file_context = file(&[A, B, A, C, B, A]);
+        file_context.duplicates();
+
+        file_context.delete_line(1).unwrap();
+        assert_matches_rebuild(&file_context);
+
+        file_context.delete_line(1).unwrap();
+        assert_matches_rebuild(&file_context);
+        assert_eq!(file_context.duplicates().later_occurrences(), [4]);
+
+        file_context.delete_line(4).unwrap();
+        assert_matches_rebuild(&file_context);
+        assert!(file_context.duplicates().later_occurrences().is_empty());
+    }
+
+    #[test]
+    fn update_moves_line_between_groups() {
+        let mut file_context = file(&[A, B, A, B]);
+        file_context.duplicates();
+
+        file_context.update_line(1, C.to_string()).unwrap();
+        assert_matches_rebuild(&file_context);
+
+        file_context.update_line(2, A.to_string()).unwrap();
+        assert_matches_rebuild(&file_context);
+        assert_eq!(file_context.duplicates().later_occurrences(), [3]);
+    }
+}
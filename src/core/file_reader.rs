@@ -1,9 +1,13 @@
 /// File reading operations
+use crate::core::duplicates::DuplicateIndex;
 use crate::error::{GliError, Result};
+use crate::models::fingerprint::Fingerprint;
 use crate::models::line::Line;
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
+use imbl::Vector;
+use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use std::time::SystemTime;
 use tempfile::NamedTempFile;
 
@@ -21,30 +25,24 @@ pub enum LineEnding {
 impl LineEnding {
     /// Detect line ending format from file content
     ///
-    /// Reads first few lines to determine the line ending format.
+    /// Returns the most common line ending in the file.
     /// Falls back to LF if no line endings are found.
     pub fn detect(file_path: &Path) -> Result<Self> {
-        let file = File::open(file_path)?;
-        let reader = BufReader::new(file);
+        Ok(FileContext::load_lossy(file_path)?.line_ending_format)
+    }
 
-        // Read first few lines to detect line ending
-        for line in reader.lines().take(10) {
-            let _ = line?; // Just need to ensure the file is readable
+    /// Most common of the given line endings (LF if there are none; ties prefer LF, then CRLF)
+    pub fn dominant(endings: impl IntoIterator<Item = LineEnding>) -> Self {
+        let mut counts = [0usize; 3];
+        for ending in endings {
+            counts[ending as usize] += 1;
         }
 
-        // Re-open file and read raw bytes to detect line endings
-        let content = fs::read_to_string(file_path)?;
-
-        if content.contains("\r\n") {
-            Ok(LineEnding::CRLF)
-        } else if content.contains('\n') {
-            Ok(LineEnding::LF)
-        } else if content.contains('\r') {
-            Ok(LineEnding::CR)
-        } else {
-            // Default to LF if no line endings found (empty file or single line)
-            Ok(LineEnding::LF)
-        }
+        [LineEnding::LF, LineEnding::CRLF, LineEnding::CR]
+            .into_iter()
+            .rev()
+            .max_by_key(|ending| counts[*ending as usize])
+            .unwrap_or(LineEnding::LF)
     }
 
     /// Convert to string representation
@@ -57,6 +55,170 @@ impl LineEnding {
     }
 }
 
+/// Text encoding of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    /// UTF-16 little endian (only detected by its byte order mark)
+    Utf16Le,
+    /// UTF-16 big endian (only detected by its byte order mark)
+    Utf16Be,
+}
+
+impl Encoding {
+    /// Detect the encoding from a byte order mark, returning it and the length of the BOM
+    fn detect(bytes: &[u8]) -> (Self, usize) {
+        match bytes {
+            [0xef, 0xbb, 0xbf, ..] => (Encoding::Utf8, 3),
+            [0xff, 0xfe, ..] => (Encoding::Utf16Le, 2),
+            [0xfe, 0xff, ..] => (Encoding::Utf16Be, 2),
+            _ => (Encoding::Utf8, 0),
+        }
+    }
+
+    /// Byte order mark of this encoding
+    fn bom(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => &[0xef, 0xbb, 0xbf],
+            Encoding::Utf16Le => &[0xff, 0xfe],
+            Encoding::Utf16Be => &[0xfe, 0xff],
+        }
+    }
+
+    /// Encode text in this encoding
+    fn encode(self, text: &str) -> Vec<u8> {
+        match self {
+            Encoding::Utf8 => text.as_bytes().to_vec(),
+            Encoding::Utf16Le => text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+            Encoding::Utf16Be => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+        }
+    }
+
+    /// Display name (e.g. "UTF-16LE")
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+        }
+    }
+}
+
+/// Split text into lines and their endings
+///
+/// Works on code units (bytes for UTF-8, `u16` for UTF-16). Lines end with `\n` or
+/// `\r\n`; a lone `\r` is only treated as a line ending in files without any `\n`
+/// (legacy Mac format) and is kept as content otherwise. Returns the lines and whether
+/// the last line is terminated.
+fn split_lines<T: Copy + PartialEq>(units: &[T], lf: T, cr: T) -> (Vec<(&[T], LineEnding)>, bool) {
+    let mut lines = Vec::new();
+    let mut rest = units;
+
+    if units.contains(&lf) {
+        while let Some(pos) = rest.iter().position(|unit| *unit == lf) {
+            let line = &rest[..pos];
+            match line.split_last() {
+                Some((last, content)) if *last == cr => lines.push((content, LineEnding::CRLF)),
+                _ => lines.push((line, LineEnding::LF)),
+            }
+            rest = &rest[pos + 1..];
+        }
+    } else {
+        while let Some(pos) = rest.iter().position(|unit| *unit == cr) {
+            lines.push((&rest[..pos], LineEnding::CR));
+            rest = &rest[pos + 1..];
+        }
+    }
+
+    let final_newline = rest.is_empty();
+    if !final_newline {
+        let ending = LineEnding::dominant(lines.iter().map(|(_, ending)| *ending));
+        lines.push((rest, ending));
+    }
+
+    (lines, final_newline)
+}
+
+/// Build a line from its decoded content, keeping the raw bytes if decoding failed
+fn decoded_line(content: String, raw: Option<Vec<u8>>, ending: LineEnding) -> Line {
+    let mut line = Line::new(content);
+    line.line_ending = ending;
+    line.raw = raw;
+    line
+}
+
+/// Decode UTF-8 content into lines
+fn decode_utf8(bytes: &[u8]) -> (Vector<Line>, bool) {
+    let (split, final_newline) = split_lines(bytes, b'\n', b'\r');
+
+    let lines = split
+        .into_iter()
+        .map(|(raw, ending)| match std::str::from_utf8(raw) {
+            Ok(content) => decoded_line(content.to_string(), None, ending),
+            Err(_) => {
+                let lossy = String::from_utf8_lossy(raw).into_owned();
+                decoded_line(lossy, Some(raw.to_vec()), ending)
+            }
+        })
+        .collect();
+
+    (lines, final_newline)
+}
+
+/// Decode UTF-16 content (without BOM) into lines
+fn decode_utf16(bytes: &[u8], encoding: Encoding) -> (Vector<Line>, bool) {
+    let pairs = bytes.chunks_exact(2);
+    let truncated = pairs.remainder().to_vec();
+    let units: Vec<u16> = pairs
+        .map(|pair| match encoding {
+            Encoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+            _ => u16::from_le_bytes([pair[0], pair[1]]),
+        })
+        .collect();
+
+    let (split, mut final_newline) = split_lines(&units, b'\n' as u16, b'\r' as u16);
+
+    let mut lines: Vector<Line> = split
+        .into_iter()
+        .map(|(raw, ending)| match String::from_utf16(raw) {
+            Ok(content) => decoded_line(content, None, ending),
+            Err(_) => {
+                let bytes = raw
+                    .iter()
+                    .flat_map(|unit| match encoding {
+                        Encoding::Utf16Be => unit.to_be_bytes(),
+                        _ => unit.to_le_bytes(),
+                    })
+                    .collect();
+                decoded_line(String::from_utf16_lossy(raw), Some(bytes), ending)
+            }
+        })
+        .collect();
+
+    // A truncated code unit at the end of the file is kept as part of the last line
+    if !truncated.is_empty() {
+        match lines.back_mut() {
+            Some(line) if !final_newline => {
+                let mut raw = line
+                    .raw
+                    .take()
+                    .unwrap_or_else(|| encoding.encode(line.content()));
+                raw.extend_from_slice(&truncated);
+                line.set_content(format!("{}{}", line.content(), char::REPLACEMENT_CHARACTER));
+                line.raw = Some(raw);
+            }
+            _ => {
+                let ending = LineEnding::dominant(lines.iter().map(|line| line.line_ending));
+                let lossy = char::REPLACEMENT_CHARACTER.to_string();
+                lines.push_back(decoded_line(lossy, Some(truncated), ending));
+                final_newline = false;
+            }
+        }
+    }
+
+    (lines, final_newline)
+}
+
 /// File context with metadata
 #[derive(Debug, Clone)]
 pub struct FileContext {
@@ -64,19 +226,44 @@ pub struct FileContext {
     pub file_path: PathBuf,
     /// Number of lines in file
     pub total_lines: usize,
-    /// Detected line ending format
+    /// Most common line ending, used for new lines
     pub line_ending_format: LineEnding,
+    /// Text encoding, kept when writing
+    pub encoding: Encoding,
+    /// File starts with a byte order mark (not part of line 1's content)
+    pub has_bom: bool,
+    /// Last line is terminated by a line ending
+    pub final_newline: bool,
     /// File modification timestamp
     pub last_modified_time: SystemTime,
     /// All lines in the file
-    pub lines: Vec<Line>,
+    ///
+    /// A persistent vector: indexing, inserting and removing lines are O(log n) and
+    /// cloning the whole file (e.g. for conflict resolution) is cheap. Modify lines
+    /// through the methods below so that the duplicate index stays current.
+    pub lines: Vector<Line>,
+    /// Duplicate fingerprints, built on first use and then updated per edited line
+    duplicates: OnceLock<DuplicateIndex>,
 }
 
 impl FileContext {
     /// Load file from path
+    ///
+    /// Fails with `InvalidEncoding` if any line is not valid UTF-8 (or UTF-16, for files
+    /// starting with a UTF-16 byte order mark).
     pub fn load<P: AsRef<Path>>(file_path: P) -> Result<Self> {
-        let path = file_path.as_ref();
+        Self::load_with(file_path.as_ref(), false)
+    }
 
+    /// Load file from path, decoding invalid bytes as U+FFFD
+    ///
+    /// Lines that could not be decoded are marked as damaged (see [`Line::is_damaged`])
+    /// and keep their original bytes when the file is written, unless they are edited.
+    pub fn load_lossy<P: AsRef<Path>>(file_path: P) -> Result<Self> {
+        Self::load_with(file_path.as_ref(), true)
+    }
+
+    fn load_with(path: &Path, lossy: bool) -> Result<Self> {
         // Check file exists
         if !path.exists() {
             return Err(GliError::FileNotFound(path.display().to_string()));
@@ -93,42 +280,33 @@ impl FileContext {
 
         let last_modified_time = metadata.modified()?;
 
-        // Detect line ending format
-        let line_ending_format = LineEnding::detect(path)?;
-
-        // Read file with line ending preservation
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-
-        let mut lines = Vec::new();
-        let mut line_number = 1;
-
-        for line_result in reader.lines() {
-            let content = line_result.map_err(|e| {
-                if let Some(err_code) = e.raw_os_error() {
-                    if err_code == 84 || err_code == 22 {
-                        // Invalid UTF-8
-                        GliError::InvalidEncoding(path.display().to_string())
-                    } else {
-                        GliError::IoError(e)
-                    }
-                } else {
-                    GliError::IoError(e)
-                }
-            })?;
-
-            lines.push(Line::new(line_number, content));
-            line_number += 1;
+        // Read raw bytes so that encoding, BOM, line endings and a missing final newline
+        // survive a write unchanged
+        let bytes = fs::read(path)?;
+        let (encoding, bom_len) = Encoding::detect(&bytes);
+        let (lines, final_newline) = match encoding {
+            Encoding::Utf8 => decode_utf8(&bytes[bom_len..]),
+            _ => decode_utf16(&bytes[bom_len..], encoding),
+        };
+
+        if !lossy && lines.iter().any(|line| line.is_damaged()) {
+            return Err(GliError::InvalidEncoding(path.display().to_string()));
         }
 
+        let line_ending_format = LineEnding::dominant(lines.iter().map(|line| line.line_ending));
+
         let total_lines = lines.len();
 
         Ok(Self {
             file_path: path.to_path_buf(),
             total_lines,
             line_ending_format,
+            encoding,
+            has_bom: bom_len > 0,
+            final_newline,
             last_modified_time,
             lines,
+            duplicates: OnceLock::new(),
         })
     }
 
@@ -162,11 +340,11 @@ impl FileContext {
         self.lines.get(line_number - 1)
     }
 
-    /// Get a range of lines
-    pub fn get_range(&self, start: usize, end: usize) -> Result<Vec<Line>> {
+    /// Get a range of lines (1-based, inclusive) without copying them
+    pub fn get_range(&self, start: usize, end: usize) -> Result<impl Iterator<Item = &Line>> {
         // Handle empty files
         if start == 0 && end == 0 {
-            return Ok(Vec::new());
+            return Ok(self.lines.focus().narrow(0..0).into_iter());
         }
 
         if start == 0 || end == 0 {
@@ -190,7 +368,12 @@ impl FileContext {
             )));
         }
 
-        Ok(self.lines[(start - 1)..end].to_vec())
+        Ok(self.lines.focus().narrow((start - 1)..end).into_iter())
+    }
+
+    /// All lines with their (1-based) line numbers
+    pub fn numbered_lines(&self) -> impl Iterator<Item = (usize, &Line)> {
+        self.lines.iter().enumerate().map(|(idx, line)| (idx + 1, line))
     }
 
     /// Write file atomically with line ending preservation
@@ -206,13 +389,25 @@ impl FileContext {
         let mut temp_file = NamedTempFile::new_in(parent)
             .map_err(|e| GliError::WriteFailure(format!("Failed to create temp file: {}", e)))?;
 
-        // Write all lines with preserved line endings
-        let line_ending = self.line_ending_format.as_str();
-        for line in &self.lines {
-            write!(temp_file, "{}{}", line.content, line_ending).map_err(|e| {
-                GliError::WriteFailure(format!("Failed to write to temp file: {}", e))
-            })?;
+        // Write all lines with preserved encoding, BOM, line endings and final newline;
+        // damaged lines are written back byte for byte
+        let mut content = Vec::new();
+        if self.has_bom {
+            content.extend_from_slice(self.encoding.bom());
         }
+        for (idx, line) in self.lines.iter().enumerate() {
+            match line.raw {
+                Some(ref raw) => content.extend_from_slice(raw),
+                None => content.extend(self.encoding.encode(line.content())),
+            }
+            if idx + 1 < self.lines.len() || self.final_newline {
+                content.extend(self.encoding.encode(line.line_ending.as_str()));
+            }
+        }
+
+        temp_file
+            .write_all(&content)
+            .map_err(|e| GliError::WriteFailure(format!("Failed to write to temp file: {}", e)))?;
 
         // Persist the temp file to the target path (atomic rename)
         temp_file
@@ -231,9 +426,13 @@ impl FileContext {
             return Err(GliError::LineOutOfBounds(line_number, self.total_lines));
         }
 
-        let line = &mut self.lines[line_number - 1];
-        line.content = new_content.clone();
-        line.pattern_type = crate::models::pattern::PatternType::parse(&new_content);
+        if let Some(index) = self.duplicates.get_mut() {
+            index.remove(line_number, &self.lines[line_number - 1]);
+        }
+        self.lines[line_number - 1].set_content(new_content);
+        if let Some(index) = self.duplicates.get_mut() {
+            index.add(line_number, &self.lines[line_number - 1], &self.lines);
+        }
 
         Ok(())
     }
@@ -244,17 +443,225 @@ impl FileContext {
             return Err(GliError::LineOutOfBounds(line_number, self.total_lines));
         }
 
-        // Remove the line from the vector
-        self.lines.remove(line_number - 1);
+        // Remove the line from the vector; later lines move up implicitly
+        let line = self.lines.remove(line_number - 1);
+        if let Some(index) = self.duplicates.get_mut() {
+            index.remove(line_number, &line);
+            index.shift(line_number + 1, -1);
+        }
 
         // Update total_lines count
         self.total_lines = self.lines.len();
 
-        // Re-number all subsequent lines
-        for (idx, line) in self.lines.iter_mut().enumerate().skip(line_number - 1) {
-            line.line_number = idx + 1;
+        Ok(())
+    }
+
+    /// Insert a line before `line_number`
+    ///
+    /// `line_number` may be `total_lines + 1` to insert after the last line.
+    pub fn insert_line(&mut self, line_number: usize, content: String) -> Result<()> {
+        if line_number == 0 || line_number > self.total_lines + 1 {
+            return Err(GliError::LineOutOfBounds(line_number, self.total_lines));
+        }
+
+        // Insert the line into the vector, using the file's usual line ending
+        let mut line = Line::new(content);
+        line.line_ending = self.line_ending_format;
+        self.lines.insert(line_number - 1, line);
+        if let Some(index) = self.duplicates.get_mut() {
+            index.shift(line_number, 1);
+            index.add(line_number, &self.lines[line_number - 1], &self.lines);
         }
 
+        // Update total_lines count
+        self.total_lines = self.lines.len();
+
         Ok(())
     }
+
+    /// Append a line to the end of the file
+    ///
+    /// Returns the line number of the appended line
+    pub fn append_line(&mut self, content: String) -> usize {
+        let line_number = self.lines.len() + 1;
+        let mut line = Line::new(content);
+        line.line_ending = self.line_ending_format;
+        self.lines.push_back(line);
+        self.total_lines = self.lines.len();
+        if let Some(index) = self.duplicates.get_mut() {
+            index.add(line_number, &self.lines[line_number - 1], &self.lines);
+        }
+        line_number
+    }
+
+    /// Index of duplicate fingerprints
+    ///
+    /// Built (classifying every line) on first use; later edits update it for the
+    /// touched lines only.
+    pub fn duplicates(&self) -> &DuplicateIndex {
+        self.duplicates
+            .get_or_init(|| DuplicateIndex::build(&self.lines))
+    }
+
+    /// All fingerprint entries with their line numbers
+    pub fn fingerprints(&self) -> impl Iterator<Item = (usize, &Fingerprint)> {
+        self.numbered_lines().filter_map(|(line_number, line)| {
+            line.pattern_type()
+                .fingerprint()
+                .map(|fingerprint| (line_number, fingerprint))
+        })
+    }
+
+    /// Line number of the first entry with the given fingerprint
+    pub fn find_fingerprint(&self, fingerprint: &Fingerprint) -> Option<usize> {
+        self.fingerprints()
+            .find(|(_, candidate)| *candidate == fingerprint)
+            .map(|(line_number, _)| line_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Load `bytes` from a file, write it back unchanged and return the written bytes
+    fn round_trip(bytes: &[u8]) -> Vec<u8> {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        fs::write(&path, bytes).unwrap();
+
+        let mut file_context = FileContext::load_lossy(&path).unwrap();
+        file_context.write_atomic().unwrap();
+        fs::read(&path).unwrap()
+    }
+
+    fn utf16(text: &str, encoding: Encoding) -> Vec<u8> {
+        let mut bytes = encoding.bom().to_vec();
+        bytes.extend(encoding.encode(text));
+        bytes
+    }
+
+    #[test]
+    fn round_trip_mixed_line_endings() {
+        let bytes = b"a.go:rule:1\r\nb.go:rule:2\nc.go:rule:3\r\n# value\rwith cr\n";
+        assert_eq!(round_trip(bytes), bytes);
+    }
+
+    #[test]
+    fn round_trip_cr_only() {
+        let bytes = b"a.go:rule:1\rb.go:rule:2\r";
+        assert_eq!(round_trip(bytes), bytes);
+    }
+
+    #[test]
+    fn round_trip_missing_final_newline() {
+        let bytes = b"a.go:rule:1\r\nb.go:rule:2";
+        assert_eq!(round_trip(bytes), bytes);
+    }
+
+    #[test]
+    fn round_trip_blank_lines_and_empty_file() {
+        assert_eq!(round_trip(b"\n\n\n"), b"\n\n\n");
+        assert_eq!(round_trip(b""), b"");
+    }
+
+    #[test]
+    fn round_trip_utf8_bom() {
+        let bytes = b"\xef\xbb\xbfa.go:rule:1\nb.go:rule:2\n";
+        assert_eq!(round_trip(bytes), bytes);
+    }
+
+    #[test]
+    fn round_trip_utf16() {
+        let text = "a.go:rule:1\r\n# \u{00e9}t\u{00e9}\nb.go:rule:2";
+        for encoding in [Encoding::Utf16Le, Encoding::Utf16Be] {
+            let bytes = utf16(text, encoding);
+            assert_eq!(round_trip(&bytes), bytes, "{}", encoding.name());
+        }
+    }
+
+    #[test]
+    fn round_trip_invalid_utf8() {
+        let bytes = b"a.go:rule:1\n# caf\xe9 \xff\xfe\r\nb.go:rule:2\n\x80";
+        assert_eq!(round_trip(bytes), bytes);
+    }
+
+    #[test]
+    fn round_trip_truncated_utf16() {
+        let mut bytes = utf16("a.go:rule:1\nb.go:rule:2", Encoding::Utf16Le);
+        bytes.push(b'x');
+        assert_eq!(round_trip(&bytes), bytes);
+    }
+
+    #[test]
+    fn edited_line_keeps_its_ending() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        fs::write(&path, b"a.go:rule:1\r\nb.go:rule:2\nc.go:rule:3").unwrap();
+
+        let mut file_context = FileContext::load(&path).unwrap();
+        file_context.update_line(1, "x.go:rule:1".to_string()).unwrap();
+        file_context.write_atomic().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"x.go:rule:1\r\nb.go:rule:2\nc.go:rule:3");
+    }
+
+    #[test]
+    fn inserted_lines_use_the_file_line_ending() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        fs::write(&path, b"a.go:rule:1\r\nb.go:rule:2\r\n").unwrap();
+
+        let mut file_context = FileContext::load(&path).unwrap();
+        file_context.insert_line(1, "first.go:rule:0".to_string()).unwrap();
+        file_context.insert_line(3, "middle.go:rule:0".to_string()).unwrap();
+        file_context.insert_line(5, "last.go:rule:0".to_string()).unwrap();
+        assert_eq!(file_context.total_lines, 5);
+        file_context.write_atomic().unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            concat!(
+                "first.go:rule:0\r\n",
+                "a.go:rule:1\r\n",
+                "middle.go:rule:0\r\n",
+                "b.go:rule:2\r\n",
+                "last.go:rule:0\r\n",
+            )
+        );
+    }
+
+    #[test]
+    fn insert_line_out_of_bounds() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        fs::write(&path, b"a.go:rule:1\n").unwrap();
+
+        let mut file_context = FileContext::load(&path).unwrap();
+        for line_number in [0, 3] {
+            assert!(matches!(
+                file_context.insert_line(line_number, "x".to_string()),
+                Err(GliError::LineOutOfBounds(n, 1)) if n == line_number
+            ));
+        }
+        assert_eq!(file_context.total_lines, 1);
+    }
+
+    #[test]
+    fn insert_line_updates_duplicates() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        fs::write(&path, b"a.go:rule:1\nb.go:rule:2\na.go:rule:1\n").unwrap();
+
+        let mut file_context = FileContext::load(&path).unwrap();
+        assert!(file_context.duplicates().is_later_occurrence(3));
+
+        // Shifts the existing duplicate and adds a new one before it
+        file_context.insert_line(2, "b.go:rule:2".to_string()).unwrap();
+        let duplicates = file_context.duplicates();
+        assert!(!duplicates.is_later_occurrence(2));
+        assert_eq!(duplicates.first_occurrence(3), Some(2));
+        assert!(duplicates.is_later_occurrence(3));
+        assert_eq!(duplicates.first_occurrence(4), Some(1));
+        assert!(duplicates.is_later_occurrence(4));
+    }
 }
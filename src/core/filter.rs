@@ -0,0 +1,193 @@
+/// Structured field filtering of fingerprint entries
+use crate::error::{GliError, Result};
+use crate::models::fingerprint::Fingerprint;
+use crate::models::pattern::PatternType;
+use glob::{MatchOptions, Pattern};
+
+/// Glob options: `*` does not cross `/`, `**` matches any number of directories
+const GLOB_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A single `field:value` filter term
+#[derive(Debug, Clone)]
+pub enum FilterTerm {
+    /// `rule:<glob>` - rule ID matches glob (plain IDs match exactly)
+    Rule(Pattern),
+    /// `path:<glob>` - file path matches glob
+    Path(Pattern),
+    /// `commit:<prefix>` - commit hash starts with prefix (case-insensitive)
+    Commit(String),
+}
+
+impl FilterTerm {
+    /// Check whether a fingerprint's fields satisfy this term
+    fn matches(&self, commit_hash: Option<&str>, file_path: &str, rule_id: &str) -> bool {
+        match self {
+            FilterTerm::Rule(pattern) => pattern.matches_with(rule_id, GLOB_OPTIONS),
+            FilterTerm::Path(pattern) => pattern.matches_with(file_path, GLOB_OPTIONS),
+            FilterTerm::Commit(prefix) => commit_hash
+                .map(|hash| hash.to_ascii_lowercase().starts_with(prefix.as_str()))
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Filter over parsed fingerprint fields
+///
+/// All terms must match (logical AND). Comments, blank lines and invalid entries
+/// never match a filter.
+#[derive(Debug, Clone)]
+pub struct FieldFilter {
+    /// Filter expression as typed by the user
+    pub expression: String,
+    pub terms: Vec<FilterTerm>,
+}
+
+impl FieldFilter {
+    /// Parse a filter expression
+    ///
+    /// Format: whitespace-separated `field:value` terms, e.g.
+    /// `rule:aws-access-token path:src/**/*.go commit:cd52267`
+    ///
+    /// Returns None for an empty expression.
+    pub fn parse(expression: &str) -> Result<Option<Self>> {
+        let mut terms = Vec::new();
+
+        for token in expression.split_whitespace() {
+            let Some((field, value)) = token.split_once(':') else {
+                return Err(GliError::InvalidFilter(format!(
+                    "expected field:value, got '{}' (fields: rule, path, commit)",
+                    token
+                )));
+            };
+
+            if value.is_empty() {
+                return Err(GliError::InvalidFilter(format!("empty value for '{}'", field)));
+            }
+
+            let term = match field {
+                "rule" => FilterTerm::Rule(Self::compile_glob(value)?),
+                "path" => FilterTerm::Path(Self::compile_glob(value)?),
+                "commit" => {
+                    if !value.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return Err(GliError::InvalidFilter(format!(
+                            "commit must be hexadecimal: '{}'",
+                            value
+                        )));
+                    }
+                    FilterTerm::Commit(value.to_ascii_lowercase())
+                }
+                _ => {
+                    return Err(GliError::InvalidFilter(format!(
+                        "unknown field '{}' (fields: rule, path, commit)",
+                        field
+                    )));
+                }
+            };
+            terms.push(term);
+        }
+
+        if terms.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            expression: expression.trim().to_string(),
+            terms,
+        }))
+    }
+
+    fn compile_glob(value: &str) -> Result<Pattern> {
+        Pattern::new(value)
+            .map_err(|e| GliError::InvalidFilter(format!("invalid glob '{}': {}", value, e)))
+    }
+
+    /// Check whether an entry matches all terms
+    pub fn matches(&self, pattern_type: &PatternType) -> bool {
+        let PatternType::Fingerprint(Fingerprint {
+            commit_hash,
+            file_path,
+            rule_id,
+            ..
+        }) = pattern_type
+        else {
+            return false;
+        };
+
+        self.terms
+            .iter()
+            .all(|term| term.matches(commit_hash.as_deref(), file_path, rule_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "cd5226711335c68be1e720b318b7bc3135a30eb2";
+
+    fn matches(expression: &str, line: &str) -> bool {
+        FieldFilter::parse(expression)
+            .unwrap()
+            .unwrap()
+            .matches(&PatternType::parse(line))
+    }
+
+    fn parse_error(expression: &str) -> String {
+        match FieldFilter::parse(expression) {
+            Err(e @ GliError::InvalidFilter(_)) => e.to_string(),
+            other => panic!("expected InvalidFilter, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn empty_expression_is_no_filter() {
+        assert!(FieldFilter::parse("  ").unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_terms_are_filter_errors() {
+        assert!(parse_error("author:me").contains("unknown field 'author'"));
+        assert!(parse_error("rule:").contains("empty value for 'rule'"));
+        assert!(parse_error("aws").contains("expected field:value"));
+        assert!(parse_error("commit:xyz").contains("hexadecimal"));
+        assert!(parse_error("path:[").contains("invalid glob"));
+        assert!(parse_error("rule:").starts_with("Error: Invalid filter:"));
+    }
+
+    #[test]
+    fn path_glob_crosses_directories_only_with_double_star() {
+        assert!(matches("path:src/**/*.go", "src/a/b/main.go:rule:1"));
+        assert!(matches("path:src/**/*.go", "src/main.go:rule:1"));
+        assert!(!matches("path:src/*.go", "src/a/main.go:rule:1"));
+        assert!(!matches("path:src/**/*.go", "lib/main.go:rule:1"));
+    }
+
+    #[test]
+    fn commit_prefix_is_case_insensitive() {
+        let line = format!("{}:src/a.go:rule:1", HASH.to_ascii_uppercase());
+        assert!(matches("commit:cd52267", &line));
+        assert!(matches("commit:CD52267", &format!("{}:src/a.go:rule:1", HASH)));
+        assert!(!matches("commit:cd53", &line));
+        // Entries without a commit never match a commit term
+        assert!(!matches("commit:cd52267", "src/a.go:rule:1"));
+    }
+
+    #[test]
+    fn terms_are_and_ed_across_fields() {
+        let line = format!("{}:src/a.go:aws-access-token:3", HASH);
+        assert!(matches("rule:aws-* path:src/*.go commit:cd5", &line));
+        assert!(!matches("rule:aws-* path:lib/*.go", &line));
+        assert!(!matches("rule:generic-api-key path:src/*.go", &line));
+    }
+
+    #[test]
+    fn non_fingerprints_never_match() {
+        assert!(!matches("rule:*", "# src/a.go:rule:1"));
+        assert!(!matches("rule:*", ""));
+        assert!(!matches("rule:*", "not a fingerprint"));
+    }
+}
@@ -0,0 +1,177 @@
+/// In-process access to the git repository of the ignore file
+use crate::error::{GliError, Result};
+use std::path::Path;
+
+/// The git repository containing the ignore file
+///
+/// Objects are read in-process (loose and packed), so no `git` executable is needed and
+/// the process's working directory does not matter.
+#[derive(Clone)]
+pub struct GitRepository {
+    repo: gix::ThreadSafeRepository,
+}
+
+impl GitRepository {
+    /// Discover the repository containing `path` (a file or directory)
+    ///
+    /// `GIT_DIR` and the other git environment variables take precedence, like they do
+    /// for git itself. Returns None outside a repository.
+    pub fn discover(path: &Path) -> Option<Self> {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            }
+        };
+
+        gix::ThreadSafeRepository::discover_with_environment_overrides(dir)
+            .ok()
+            .map(|repo| Self { repo })
+    }
+
+    /// Root of the working tree (None for bare repositories)
+    pub fn workdir(&self) -> Option<&Path> {
+        self.repo.work_dir()
+    }
+
+    /// The repository's `.git` directory
+    pub fn git_dir(&self) -> &Path {
+        self.repo.path()
+    }
+
+    /// Content of `file_path` (relative to the repository root) at a commit
+    pub fn read_blob(&self, commit_hash: &str, file_path: &str) -> Result<Vec<u8>> {
+        let repo = self.repo.to_thread_local();
+        let id = Self::find_blob(&repo, commit_hash, file_path)?;
+        let blob = repo
+            .find_object(id)
+            .map_err(|e| GliError::FileNotFound(format!("{}:{}: {}", commit_hash, file_path, e)))?;
+        Ok(blob.detach().data)
+    }
+
+    /// Whether `file_path` exists in a commit (false if the commit does not exist)
+    pub fn contains(&self, commit_hash: &str, file_path: &str) -> bool {
+        let repo = self.repo.to_thread_local();
+        Self::find_blob(&repo, commit_hash, file_path).is_ok()
+    }
+
+    /// Look up the blob id of `file_path` in a commit's tree
+    fn find_blob(
+        repo: &gix::Repository,
+        commit_hash: &str,
+        file_path: &str,
+    ) -> Result<gix::ObjectId> {
+        let not_found = |reason: String| {
+            GliError::FileNotFound(format!("{}:{}: {}", commit_hash, file_path, reason))
+        };
+
+        let id = gix::ObjectId::from_hex(commit_hash.as_bytes())
+            .map_err(|e| not_found(e.to_string()))?;
+        let tree = repo
+            .find_object(id)
+            .map_err(|e| not_found(e.to_string()))?
+            .peel_to_tree()
+            .map_err(|e| not_found(e.to_string()))?;
+
+        tree.lookup_entry_by_path(file_path)
+            .map_err(|e| not_found(e.to_string()))?
+            .filter(|entry| entry.mode().is_blob())
+            .map(|entry| entry.object_id())
+            .ok_or_else(|| not_found("no such file in commit".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    /// Run git in `dir` and return its trimmed output
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// Repository with `src/a.go` committed; returns the directory and the commit
+    fn repository() -> (TempDir, String) {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/a.go"), "package a\n").unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["add", "-A"]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        let commit = git(dir.path(), &["rev-parse", "HEAD"]);
+        (dir, commit)
+    }
+
+    #[test]
+    fn discovers_repository_from_a_nested_file() {
+        let (dir, _) = repository();
+        let repository = GitRepository::discover(&dir.path().join("src/.gitleaksignore")).unwrap();
+        assert_eq!(
+            repository.workdir().unwrap().canonicalize().unwrap(),
+            dir.path().canonicalize().unwrap()
+        );
+
+        let outside = TempDir::new().unwrap();
+        assert!(GitRepository::discover(&outside.path().join(".gitleaksignore")).is_none());
+    }
+
+    #[test]
+    fn reads_loose_and_packed_blobs() {
+        let (dir, commit) = repository();
+        let repository = GitRepository::discover(dir.path()).unwrap();
+        assert_eq!(repository.read_blob(&commit, "src/a.go").unwrap(), b"package a\n");
+
+        git(dir.path(), &["gc", "-q"]);
+        let repository = GitRepository::discover(dir.path()).unwrap();
+        assert_eq!(repository.read_blob(&commit, "src/a.go").unwrap(), b"package a\n");
+    }
+
+    #[test]
+    fn contains_only_files_of_existing_commits() {
+        let (dir, commit) = repository();
+        let repository = GitRepository::discover(dir.path()).unwrap();
+
+        assert!(repository.contains(&commit, "src/a.go"));
+        assert!(!repository.contains(&commit, "src/b.go"));
+        // Directories are not files
+        assert!(!repository.contains(&commit, "src"));
+        assert!(!repository.contains(&"0".repeat(40), "src/a.go"));
+        assert!(matches!(
+            repository.read_blob("not-a-hash", "src/a.go"),
+            Err(GliError::FileNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn reads_blobs_from_a_linked_worktree() {
+        let (dir, commit) = repository();
+        let worktrees = TempDir::new().unwrap();
+        let worktree = worktrees.path().join("linked");
+        git(dir.path(), &["worktree", "add", "-q", worktree.to_str().unwrap()]);
+
+        let repository = GitRepository::discover(&worktree.join(".gitleaksignore")).unwrap();
+        assert_eq!(
+            repository.workdir().unwrap().canonicalize().unwrap(),
+            worktree.canonicalize().unwrap()
+        );
+        assert_eq!(repository.read_blob(&commit, "src/a.go").unwrap(), b"package a\n");
+    }
+}
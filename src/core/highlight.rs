@@ -0,0 +1,629 @@
+/// Lightweight syntax highlighting of previewed source files
+use std::path::Path;
+
+/// Kind of a highlighted token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Comment,
+    String,
+    Keyword,
+    Number,
+    /// Key of a key/value pair (YAML, JSON, TOML, .env)
+    Key,
+}
+
+/// A highlighted byte range of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Languages recognised from the file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Go,
+    Python,
+    /// JavaScript and TypeScript
+    JavaScript,
+    Rust,
+    Shell,
+    Json,
+    Yaml,
+    Toml,
+    /// `.env` files
+    Env,
+}
+
+/// String literal delimiters
+struct Quote {
+    open: &'static str,
+    close: &'static str,
+    /// Backslash escapes the next character
+    escapes: bool,
+    /// May span several lines
+    multiline: bool,
+}
+
+const fn quote(delimiter: &'static str, escapes: bool, multiline: bool) -> Quote {
+    Quote {
+        open: delimiter,
+        close: delimiter,
+        escapes,
+        multiline,
+    }
+}
+
+/// Lexical rules of a language
+struct Syntax {
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Line comments must start the line or follow whitespace (`#` in YAML and shell)
+    comment_after_space: bool,
+    /// Longer delimiters first
+    quotes: &'static [Quote],
+    keywords: &'static [&'static str],
+    /// Separator after a key at the start of a line (`:` or `=`)
+    key_separator: Option<u8>,
+    /// Strings followed by `:` are keys (JSON and YAML)
+    quoted_keys: bool,
+}
+
+const GO: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    comment_after_space: false,
+    quotes: &[
+        quote("\"", true, false),
+        quote("'", true, false),
+        quote("`", false, true),
+    ],
+    keywords: &[
+        "break",
+        "case",
+        "chan",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "else",
+        "fallthrough",
+        "false",
+        "for",
+        "func",
+        "go",
+        "goto",
+        "if",
+        "import",
+        "interface",
+        "map",
+        "nil",
+        "package",
+        "range",
+        "return",
+        "select",
+        "struct",
+        "switch",
+        "true",
+        "type",
+        "var",
+    ],
+    key_separator: None,
+    quoted_keys: false,
+};
+
+const PYTHON: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comment: None,
+    comment_after_space: false,
+    quotes: &[
+        quote("\"\"\"", true, true),
+        quote("'''", true, true),
+        quote("\"", true, false),
+        quote("'", true, false),
+    ],
+    keywords: &[
+        "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+        "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+        "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+        "self", "try", "while", "with", "yield",
+    ],
+    key_separator: None,
+    quoted_keys: false,
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    comment_after_space: false,
+    quotes: &[
+        quote("\"", true, false),
+        quote("'", true, false),
+        quote("`", true, true),
+    ],
+    keywords: &[
+        "as",
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "if",
+        "implements",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "private",
+        "protected",
+        "public",
+        "readonly",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "type",
+        "typeof",
+        "undefined",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    key_separator: None,
+    quoted_keys: false,
+};
+
+const RUST: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    comment_after_space: false,
+    quotes: &[quote("\"", true, true)],
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+        "type", "unsafe", "use", "where", "while",
+    ],
+    key_separator: None,
+    quoted_keys: false,
+};
+
+const SHELL: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comment: None,
+    comment_after_space: true,
+    quotes: &[quote("\"", true, true), quote("'", false, true)],
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "then", "until", "while",
+    ],
+    key_separator: Some(b'='),
+    quoted_keys: false,
+};
+
+const JSON: Syntax = Syntax {
+    line_comments: &[],
+    block_comment: None,
+    comment_after_space: false,
+    quotes: &[quote("\"", true, false)],
+    keywords: &["false", "null", "true"],
+    key_separator: None,
+    quoted_keys: true,
+};
+
+const YAML: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comment: None,
+    comment_after_space: true,
+    quotes: &[quote("\"", true, false), quote("'", true, false)],
+    keywords: &["false", "no", "null", "off", "on", "true", "yes"],
+    key_separator: Some(b':'),
+    quoted_keys: true,
+};
+
+const TOML: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comment: None,
+    comment_after_space: false,
+    quotes: &[
+        quote("\"\"\"", true, true),
+        quote("'''", false, true),
+        quote("\"", true, false),
+        quote("'", false, false),
+    ],
+    keywords: &["false", "true"],
+    key_separator: Some(b'='),
+    quoted_keys: false,
+};
+
+const ENV: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comment: None,
+    comment_after_space: true,
+    quotes: &[quote("\"", true, true), quote("'", false, true)],
+    keywords: &["export"],
+    key_separator: Some(b'='),
+    quoted_keys: false,
+};
+
+impl Language {
+    /// Detect the language from a file path's name or extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        let path = Path::new(path);
+        let name = path.file_name()?.to_str()?;
+        if name == ".env" || name.starts_with(".env.") {
+            return Some(Language::Env);
+        }
+
+        let language = match path.extension()?.to_str()? {
+            "go" => Language::Go,
+            "py" | "pyi" => Language::Python,
+            "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Language::JavaScript,
+            "rs" => Language::Rust,
+            "sh" | "bash" | "zsh" => Language::Shell,
+            "json" => Language::Json,
+            "yaml" | "yml" => Language::Yaml,
+            "toml" => Language::Toml,
+            "env" => Language::Env,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    fn syntax(self) -> &'static Syntax {
+        match self {
+            Language::Go => &GO,
+            Language::Python => &PYTHON,
+            Language::JavaScript => &JAVASCRIPT,
+            Language::Rust => &RUST,
+            Language::Shell => &SHELL,
+            Language::Json => &JSON,
+            Language::Yaml => &YAML,
+            Language::Toml => &TOML,
+            Language::Env => &ENV,
+        }
+    }
+
+    /// Tokens of every line, in order
+    ///
+    /// Block comments and multi-line strings are carried over from one line to the next.
+    pub fn highlight<S: AsRef<str>>(self, lines: &[S]) -> Vec<Vec<Token>> {
+        let mut lexer = Lexer {
+            syntax: self.syntax(),
+            open: None,
+        };
+        lines.iter().map(|line| lexer.line(line.as_ref())).collect()
+    }
+}
+
+/// Lexer state carried between lines
+struct Lexer {
+    syntax: &'static Syntax,
+    /// Unterminated block comment or string: its kind, closing delimiter and escaping
+    open: Option<(TokenKind, &'static str, bool)>,
+}
+
+impl Lexer {
+    /// Tokens of one line
+    fn line(&mut self, line: &str) -> Vec<Token> {
+        let bytes = line.as_bytes();
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+        let mut key = None;
+
+        // Continue a block comment or string from the previous line
+        if let Some((kind, close, escapes)) = self.open {
+            match find_close(line, 0, close, escapes) {
+                Some(end) => {
+                    self.open = None;
+                    pos = end;
+                }
+                None => pos = line.len(),
+            }
+            tokens.push(Token {
+                kind,
+                start: 0,
+                end: pos,
+            });
+        } else {
+            key = self.key_range(line);
+        }
+
+        while pos < line.len() {
+            if let Some((start, end)) = key.filter(|(start, _)| *start == pos) {
+                tokens.push(Token {
+                    kind: TokenKind::Key,
+                    start,
+                    end,
+                });
+                pos = end;
+                continue;
+            }
+
+            let rest = &line[pos..];
+            let after_space = pos == 0 || bytes[pos - 1].is_ascii_whitespace();
+
+            if (after_space || !self.syntax.comment_after_space)
+                && self
+                    .syntax
+                    .line_comments
+                    .iter()
+                    .any(|prefix| rest.starts_with(prefix))
+            {
+                tokens.push(Token {
+                    kind: TokenKind::Comment,
+                    start: pos,
+                    end: line.len(),
+                });
+                break;
+            }
+
+            if let Some((open, close)) = self.syntax.block_comment {
+                if rest.starts_with(open) {
+                    let end = find_close(line, pos + open.len(), close, false);
+                    if end.is_none() {
+                        self.open = Some((TokenKind::Comment, close, false));
+                    }
+                    let end = end.unwrap_or(line.len());
+                    tokens.push(Token {
+                        kind: TokenKind::Comment,
+                        start: pos,
+                        end,
+                    });
+                    pos = end;
+                    continue;
+                }
+            }
+
+            if let Some(quote) = self.syntax.quotes.iter().find(|q| rest.starts_with(q.open)) {
+                let end = find_close(line, pos + quote.open.len(), quote.close, quote.escapes);
+                if end.is_none() && quote.multiline {
+                    self.open = Some((TokenKind::String, quote.close, quote.escapes));
+                }
+                let end = end.unwrap_or(line.len());
+
+                // A quoted key in JSON or YAML (`"key": value`)
+                let is_key = self.syntax.quoted_keys && line[end..].trim_start().starts_with(':');
+                let kind = if is_key {
+                    TokenKind::Key
+                } else {
+                    TokenKind::String
+                };
+                tokens.push(Token {
+                    kind,
+                    start: pos,
+                    end,
+                });
+                pos = end;
+                continue;
+            }
+
+            let c = rest.chars().next().unwrap_or(' ');
+            let word_len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            let starts_word = pos == 0 || !is_word_byte(bytes[pos - 1]);
+
+            if c.is_ascii_digit() && starts_word {
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+                    .unwrap_or(rest.len());
+                tokens.push(Token {
+                    kind: TokenKind::Number,
+                    start: pos,
+                    end: pos + len,
+                });
+                pos += len;
+            } else if word_len > 0 && starts_word {
+                if self.syntax.keywords.contains(&&rest[..word_len]) {
+                    tokens.push(Token {
+                        kind: TokenKind::Keyword,
+                        start: pos,
+                        end: pos + word_len,
+                    });
+                }
+                pos += word_len;
+            } else {
+                pos += c.len_utf8();
+            }
+        }
+
+        tokens
+    }
+
+    /// Byte range of an unquoted key at the start of the line (`key:` in YAML, `KEY=` in
+    /// .env)
+    ///
+    /// The key may be preceded by a YAML list marker or `export`.
+    fn key_range(&self, line: &str) -> Option<(usize, usize)> {
+        let separator = self.syntax.key_separator?;
+        let indent = line.len() - line.trim_start().len();
+        let mut start = indent;
+        for prefix in ["- ", "export "] {
+            if line[start..].starts_with(prefix) {
+                start += prefix.len();
+            }
+        }
+
+        let key_len = line[start..]
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '/')))
+            .unwrap_or(line.len() - start);
+        let end = start + key_len;
+        let is_key = key_len > 0 && line[end..].trim_start().as_bytes().first() == Some(&separator);
+        is_key.then_some((start, end))
+    }
+}
+
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$' || byte >= 0x80
+}
+
+/// Byte offset just past the closing delimiter, searching from `from`
+fn find_close(line: &str, from: usize, close: &str, escapes: bool) -> Option<usize> {
+    let mut pos = from;
+    while pos < line.len() {
+        let rest = &line[pos..];
+        if rest.starts_with(close) {
+            return Some(pos + close.len());
+        }
+        let c = rest.chars().next()?;
+        pos += c.len_utf8();
+        if escapes && c == '\\' {
+            pos += line[pos..].chars().next().map_or(0, char::len_utf8);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TokenKind::*;
+
+    /// Tokens of each line as `(kind, text)`, checking that they lie on char boundaries
+    fn spans<'a>(language: Language, lines: &[&'a str]) -> Vec<Vec<(TokenKind, &'a str)>> {
+        language
+            .highlight(lines)
+            .into_iter()
+            .zip(lines)
+            .map(|(tokens, line)| {
+                tokens
+                    .into_iter()
+                    .map(|token| {
+                        assert!(line.is_char_boundary(token.start), "{:?} in {:?}", token, line);
+                        assert!(line.is_char_boundary(token.end), "{:?} in {:?}", token, line);
+                        (token.kind, &line[token.start..token.end])
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn block_comment_continues_across_lines() {
+        let lines = ["x := 1 /* start", "middle", "end */ y := \"s\""];
+        assert_eq!(
+            spans(Language::Go, &lines),
+            [
+                vec![(Number, "1"), (Comment, "/* start")],
+                vec![(Comment, "middle")],
+                vec![(Comment, "end */"), (String, "\"s\"")],
+            ]
+        );
+    }
+
+    #[test]
+    fn multiline_string_continues_across_lines() {
+        let lines = ["s = \"\"\"one", "two", "three\"\"\" # done"];
+        assert_eq!(
+            spans(Language::Python, &lines),
+            [
+                vec![(String, "\"\"\"one")],
+                vec![(String, "two")],
+                vec![(String, "three\"\"\""), (Comment, "# done")],
+            ]
+        );
+
+        let lines = ["key = '''", "raw \\'''", "n = 2"];
+        assert_eq!(
+            spans(Language::Toml, &lines),
+            [
+                vec![(Key, "key"), (String, "'''")],
+                vec![(String, "raw \\'''")],
+                vec![(Key, "n"), (Number, "2")],
+            ]
+        );
+    }
+
+    #[test]
+    fn single_line_string_ends_with_the_line() {
+        let lines = ["s := \"abc", "// c"];
+        assert_eq!(
+            spans(Language::Go, &lines),
+            [vec![(String, "\"abc")], vec![(Comment, "// c")]]
+        );
+    }
+
+    #[test]
+    fn yaml_comment_needs_preceding_space() {
+        let lines = ["key: a#b # note", "url: http://x/#frag", "# whole line"];
+        assert_eq!(
+            spans(Language::Yaml, &lines),
+            [
+                vec![(Key, "key"), (Comment, "# note")],
+                vec![(Key, "url")],
+                vec![(Comment, "# whole line")],
+            ]
+        );
+    }
+
+    #[test]
+    fn env_keys_after_export() {
+        let lines = ["export API_KEY=\"abc\"", "# comment", "PLAIN=1", "  export X = y"];
+        assert_eq!(
+            spans(Language::Env, &lines),
+            [
+                vec![(Keyword, "export"), (Key, "API_KEY"), (String, "\"abc\"")],
+                vec![(Comment, "# comment")],
+                vec![(Key, "PLAIN"), (Number, "1")],
+                vec![(Keyword, "export"), (Key, "X")],
+            ]
+        );
+    }
+
+    #[test]
+    fn multibyte_text_keeps_char_boundaries() {
+        let lines = ["ключ = \"секрет\" # комментарий", "naïve_1 = 'é' é1 2"];
+        assert_eq!(
+            spans(Language::Python, &lines),
+            [
+                vec![(String, "\"секрет\""), (Comment, "# комментарий")],
+                vec![(String, "'é'"), (Number, "2")],
+            ]
+        );
+
+        let lines = ["{\"clé\": \"välue\", \"n\": 1}"];
+        assert_eq!(
+            spans(Language::Json, &lines),
+            [vec![(Key, "\"clé\""), (String, "\"välue\""), (Key, "\"n\""), (Number, "1")]]
+        );
+
+        let lines = ["emoji: \"😀\" # ☃"];
+        assert_eq!(
+            spans(Language::Yaml, &lines),
+            [vec![(Key, "emoji"), (String, "\"😀\""), (Comment, "# ☃")]]
+        );
+    }
+
+    #[test]
+    fn language_from_path() {
+        assert_eq!(Language::from_path("config/.env.local"), Some(Language::Env));
+        assert_eq!(Language::from_path("deploy/app.yml"), Some(Language::Yaml));
+        assert_eq!(Language::from_path("web/index.tsx"), Some(Language::JavaScript));
+        assert_eq!(Language::from_path("Makefile"), None);
+    }
+}
@@ -0,0 +1,547 @@
+/// Edit operation history for undo/redo
+use crate::core::diff::{self, DiffKind};
+use crate::core::file_reader::FileContext;
+use crate::error::{GliError, Result};
+use std::time::SystemTime;
+
+/// Maximum number of changes kept on the undo stack
+const MAX_HISTORY: usize = 100;
+
+/// Type of modification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationType {
+    /// Modified existing line
+    Update,
+    /// Added new line
+    Insert,
+    /// Removed line
+    Delete,
+}
+
+/// Represents a modification to a specific line
+///
+/// Also known as Edit Operation in data-model.md
+#[derive(Debug, Clone)]
+pub struct EditOperation {
+    /// Type of modification
+    pub operation_type: OperationType,
+    /// Target line (1-based)
+    pub line_number: usize,
+    /// Content before edit (empty for Insert)
+    pub original_content: String,
+    /// Content after edit (empty for Delete)
+    pub new_content: String,
+    /// When edit was performed
+    pub timestamp: SystemTime,
+}
+
+impl EditOperation {
+    fn new(
+        operation_type: OperationType,
+        line_number: usize,
+        original_content: String,
+        new_content: String,
+    ) -> Self {
+        Self {
+            operation_type,
+            line_number,
+            original_content,
+            new_content,
+            timestamp: SystemTime::now(),
+        }
+    }
+
+    /// Create an Update operation
+    pub fn update(line_number: usize, original_content: String, new_content: String) -> Self {
+        Self::new(OperationType::Update, line_number, original_content, new_content)
+    }
+
+    /// Create an Insert operation
+    pub fn insert(line_number: usize, content: String) -> Self {
+        Self::new(OperationType::Insert, line_number, String::new(), content)
+    }
+
+    /// Create a Delete operation
+    pub fn delete(line_number: usize, content: String) -> Self {
+        Self::new(OperationType::Delete, line_number, content, String::new())
+    }
+
+    /// Execute the edit operation on file content
+    pub fn apply(&self, file_context: &mut FileContext) -> Result<()> {
+        match self.operation_type {
+            OperationType::Update => {
+                self.expect_content(file_context, &self.original_content)?;
+                file_context.update_line(self.line_number, self.new_content.clone())
+            }
+            OperationType::Insert => {
+                file_context.insert_line(self.line_number, self.new_content.clone())
+            }
+            OperationType::Delete => {
+                self.expect_content(file_context, &self.original_content)?;
+                file_context.delete_line(self.line_number)
+            }
+        }
+    }
+
+    /// Restore original content
+    pub fn revert(&self, file_context: &mut FileContext) -> Result<()> {
+        match self.operation_type {
+            OperationType::Update => {
+                self.expect_content(file_context, &self.new_content)?;
+                file_context.update_line(self.line_number, self.original_content.clone())
+            }
+            OperationType::Insert => {
+                self.expect_content(file_context, &self.new_content)?;
+                file_context.delete_line(self.line_number)
+            }
+            OperationType::Delete => {
+                file_context.insert_line(self.line_number, self.original_content.clone())
+            }
+        }
+    }
+
+    /// Operation undoing this one
+    pub fn inverse(&self) -> Self {
+        let operation_type = match self.operation_type {
+            OperationType::Update => OperationType::Update,
+            OperationType::Insert => OperationType::Delete,
+            OperationType::Delete => OperationType::Insert,
+        };
+        Self::new(
+            operation_type,
+            self.line_number,
+            self.new_content.clone(),
+            self.original_content.clone(),
+        )
+    }
+
+    /// Check that the target line still holds the expected content
+    fn expect_content(&self, file_context: &FileContext, expected: &str) -> Result<()> {
+        match file_context.get_line(self.line_number) {
+            Some(line) if line.content() == expected => Ok(()),
+            Some(_) => Err(GliError::ConcurrentModification(format!(
+                "line {} no longer matches the recorded edit",
+                self.line_number
+            ))),
+            None => Err(GliError::LineOutOfBounds(self.line_number, file_context.total_lines)),
+        }
+    }
+}
+
+/// A group of operations produced by a single user action
+#[derive(Debug, Clone)]
+pub struct Change {
+    /// Short description shown in the status line (e.g. "delete line 42")
+    pub description: String,
+    /// Operations in the order they were applied
+    pub operations: Vec<EditOperation>,
+}
+
+impl Change {
+    pub fn new(description: impl Into<String>, operations: Vec<EditOperation>) -> Self {
+        Self {
+            description: description.into(),
+            operations,
+        }
+    }
+
+    /// Change turning the lines of `from` into the lines of `to`
+    ///
+    /// Unchanged lines are left alone (keeping their line endings); the operations come
+    /// from a line diff of the two files.
+    pub fn between(description: impl Into<String>, from: &FileContext, to: &FileContext) -> Self {
+        let from_lines: Vec<&str> = from.lines.iter().map(|l| l.content()).collect();
+        let to_lines: Vec<&str> = to.lines.iter().map(|l| l.content()).collect();
+
+        // Line number in the file as it is while the operations are applied in order
+        let mut line_number = 1;
+        let mut operations = Vec::new();
+        for line in diff::diff_lines(&from_lines, &to_lines) {
+            match line.kind {
+                DiffKind::Equal => line_number += 1,
+                DiffKind::Removed => {
+                    operations.push(EditOperation::delete(line_number, line.content));
+                }
+                DiffKind::Added => {
+                    operations.push(EditOperation::insert(line_number, line.content));
+                    line_number += 1;
+                }
+            }
+        }
+
+        Self::new(description, operations)
+    }
+
+    /// When the change was made (the time of its first operation)
+    pub fn made_at(&self) -> Option<SystemTime> {
+        self.operations.first().map(|op| op.timestamp)
+    }
+
+    /// Line the cursor should land on after applying or reverting this change
+    pub fn anchor_line(&self) -> usize {
+        self.operations.first().map(|op| op.line_number).unwrap_or(1)
+    }
+
+    /// Change whose application reverts this one
+    pub fn inverse(&self, description: impl Into<String>) -> Self {
+        let operations = self.operations.iter().rev().map(EditOperation::inverse).collect();
+        Self::new(description, operations)
+    }
+
+    /// Apply all operations, rolling back if any of them fails
+    pub fn apply(&self, file_context: &mut FileContext) -> Result<()> {
+        for (idx, op) in self.operations.iter().enumerate() {
+            if let Err(e) = op.apply(file_context) {
+                for applied in self.operations[..idx].iter().rev() {
+                    let _ = applied.revert(file_context);
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Revert all operations in reverse order, rolling back if any of them fails
+    pub fn revert(&self, file_context: &mut FileContext) -> Result<()> {
+        for (idx, op) in self.operations.iter().enumerate().rev() {
+            if let Err(e) = op.revert(file_context) {
+                for reverted in &self.operations[idx + 1..] {
+                    let _ = reverted.apply(file_context);
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Move this change onto a newer version of the file
+    ///
+    /// `base` is the file the change was made against and `target` the newer version.
+    /// Each operation is moved to the line matching its original position in a diff of
+    /// the two; fails if a line the change touches was itself modified in `target`.
+    pub fn rebase(&self, base: &FileContext, target: &FileContext) -> Result<Change> {
+        let base_lines: Vec<&str> = base.lines.iter().map(|l| l.content()).collect();
+        let target_lines: Vec<&str> = target.lines.iter().map(|l| l.content()).collect();
+
+        // Distance from each line of the base (as changed by the operations so far) to the
+        // same line in the target, None if the target changed it. Both files receive the
+        // same operations, so lines after an inserted or deleted one keep their distance
+        // and the diff only runs once.
+        let mut offsets: Vec<Option<isize>> = diff::line_mapping(&base_lines, &target_lines)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, line)| line.map(|line| line as isize - (idx + 1) as isize))
+            .collect();
+        let mapped = |offsets: &[Option<isize>], line_number: usize| {
+            let offset = offsets.get(line_number.checked_sub(1)?).copied().flatten()?;
+            line_number.checked_add_signed(offset)
+        };
+
+        let mut target = target.clone();
+        let mut operations = Vec::new();
+
+        for op in &self.operations {
+            // Inserted lines follow the line they were inserted after
+            let line_number = match op.operation_type {
+                OperationType::Insert if op.line_number == 1 => Some(1),
+                OperationType::Insert => {
+                    mapped(&offsets, op.line_number - 1).map(|line| line + 1)
+                }
+                OperationType::Update | OperationType::Delete => mapped(&offsets, op.line_number),
+            };
+
+            let Some(line_number) = line_number else {
+                return Err(GliError::ConcurrentModification(format!(
+                    "line {} was also changed on disk",
+                    op.line_number
+                )));
+            };
+
+            let rebased = EditOperation {
+                line_number,
+                ..op.clone()
+            };
+            rebased.apply(&mut target)?;
+
+            match op.operation_type {
+                OperationType::Insert => offsets.insert(
+                    op.line_number - 1,
+                    Some(line_number as isize - op.line_number as isize),
+                ),
+                OperationType::Delete => {
+                    offsets.remove(op.line_number - 1);
+                }
+                OperationType::Update => {}
+            }
+            operations.push(rebased);
+        }
+
+        Ok(Change::new(self.description.clone(), operations))
+    }
+}
+
+/// Undo/redo stacks of applied changes
+#[derive(Debug, Default)]
+pub struct History {
+    undo_stack: Vec<Change>,
+    redo_stack: Vec<Change>,
+}
+
+impl History {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget all changes (e.g. after the file was reloaded from disk)
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// Record a change that has just been applied
+    ///
+    /// Recording a new change discards the redo stack.
+    pub fn record(&mut self, change: Change) {
+        if change.operations.is_empty() {
+            return;
+        }
+        self.undo_stack.push(change);
+        if self.undo_stack.len() > MAX_HISTORY {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+    }
+
+    /// Change that would be reverted by the next undo
+    pub fn next_undo(&self) -> Option<&Change> {
+        self.undo_stack.last()
+    }
+
+    /// Change that would be re-applied by the next redo
+    pub fn next_redo(&self) -> Option<&Change> {
+        self.redo_stack.last()
+    }
+
+    /// Revert the most recent change
+    ///
+    /// Returns the reverted change, or None if there is nothing to undo.
+    pub fn undo(&mut self, file_context: &mut FileContext) -> Result<Option<Change>> {
+        let Some(change) = self.undo_stack.pop() else {
+            return Ok(None);
+        };
+
+        if let Err(e) = change.revert(file_context) {
+            self.undo_stack.push(change);
+            return Err(e);
+        }
+
+        self.redo_stack.push(change.clone());
+        Ok(Some(change))
+    }
+
+    /// Re-apply the most recently undone change
+    ///
+    /// Returns the re-applied change, or None if there is nothing to redo.
+    pub fn redo(&mut self, file_context: &mut FileContext) -> Result<Option<Change>> {
+        let Some(change) = self.redo_stack.pop() else {
+            return Ok(None);
+        };
+
+        if let Err(e) = change.apply(file_context) {
+            self.redo_stack.push(change);
+            return Err(e);
+        }
+
+        self.undo_stack.push(change.clone());
+        Ok(Some(change))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn file(lines: &[&str]) -> FileContext {
+        let mut temp = NamedTempFile::new().unwrap();
+        for line in lines {
+            writeln!(temp, "{}", line).unwrap();
+        }
+        FileContext::load(temp.path()).unwrap()
+    }
+
+    fn contents(file: &FileContext) -> Vec<&str> {
+        file.lines.iter().map(|line| line.content()).collect()
+    }
+
+    /// Rebase `change` from `base` onto `disk` and apply it to `disk`
+    fn merge(change: &Change, base: &[&str], disk: &[&str]) -> Result<Vec<String>> {
+        let mut target = file(disk);
+        let rebased = change.rebase(&file(base), &target)?;
+        rebased.apply(&mut target)?;
+        Ok(contents(&target).into_iter().map(String::from).collect())
+    }
+
+    #[test]
+    fn rebase_insert_at_line_one() {
+        let change = Change::new("insert", vec![EditOperation::insert(1, "x".into())]);
+        let merged = merge(&change, &["a", "b"], &["a", "b", "c"]).unwrap();
+        assert_eq!(merged, ["x", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn rebase_insert_follows_shifted_line() {
+        let change = Change::new("insert", vec![EditOperation::insert(3, "x".into())]);
+        let merged = merge(&change, &["a", "b", "c"], &["z", "a", "b", "c"]).unwrap();
+        assert_eq!(merged, ["z", "a", "b", "x", "c"]);
+    }
+
+    #[test]
+    fn rebase_insert_after_changed_line_conflicts() {
+        let change = Change::new("insert", vec![EditOperation::insert(3, "x".into())]);
+        let result = merge(&change, &["a", "b"], &["a", "B"]);
+        assert!(matches!(result, Err(GliError::ConcurrentModification(_))));
+    }
+
+    #[test]
+    fn rebase_delete_last_line() {
+        let change = Change::new("delete", vec![EditOperation::delete(3, "c".into())]);
+        let merged = merge(&change, &["a", "b", "c"], &["z", "a", "b", "c"]).unwrap();
+        assert_eq!(merged, ["z", "a", "b"]);
+    }
+
+    #[test]
+    fn rebase_update_of_line_changed_on_disk_conflicts() {
+        let change = Change::new("edit", vec![EditOperation::update(2, "b".into(), "y".into())]);
+        let result = merge(&change, &["a", "b", "c"], &["a", "B", "c"]);
+        assert!(matches!(result, Err(GliError::ConcurrentModification(_))));
+    }
+
+    #[test]
+    fn rebase_bulk_change_keeps_offsets_in_step() {
+        let change = Change::new(
+            "delete lines",
+            vec![
+                EditOperation::delete(1, "a".into()),
+                EditOperation::delete(1, "b".into()),
+                EditOperation::insert(2, "y".into()),
+                EditOperation::update(3, "d".into(), "D".into()),
+            ],
+        );
+        let merged = merge(&change, &["a", "b", "c", "d"], &["a", "x", "b", "c", "d", "e"]);
+        assert_eq!(merged.unwrap(), ["x", "c", "y", "D", "e"]);
+    }
+
+    /// Update line 1 to `content`, recording the change
+    fn edit(history: &mut History, file_context: &mut FileContext, content: &str) {
+        let original = file_context.get_line(1).unwrap().content().to_string();
+        let change = Change::new(
+            format!("edit {}", content),
+            vec![EditOperation::update(1, original, content.to_string())],
+        );
+        change.apply(file_context).unwrap();
+        history.record(change);
+    }
+
+    #[test]
+    fn undo_at_oldest_change_returns_none() {
+        let mut history = History::new();
+        let mut file_context = file(&["a"]);
+        assert!(history.undo(&mut file_context).unwrap().is_none());
+
+        edit(&mut history, &mut file_context, "b");
+        let undone = history.undo(&mut file_context).unwrap().unwrap();
+        assert_eq!(undone.description, "edit b");
+        assert!(undone.made_at().is_some());
+        assert_eq!(contents(&file_context), ["a"]);
+        assert!(history.undo(&mut file_context).unwrap().is_none());
+        assert!(history.redo(&mut file_context).unwrap().is_some());
+        assert!(history.redo(&mut file_context).unwrap().is_none());
+        assert_eq!(contents(&file_context), ["b"]);
+    }
+
+    #[test]
+    fn record_clears_redo() {
+        let mut history = History::new();
+        let mut file_context = file(&["a"]);
+        edit(&mut history, &mut file_context, "b");
+        history.undo(&mut file_context).unwrap();
+        assert!(history.next_redo().is_some());
+
+        edit(&mut history, &mut file_context, "c");
+        assert!(history.next_redo().is_none());
+        assert!(history.redo(&mut file_context).unwrap().is_none());
+        assert_eq!(contents(&file_context), ["c"]);
+    }
+
+    #[test]
+    fn history_keeps_the_last_max_history_changes() {
+        let mut history = History::new();
+        let mut file_context = file(&["0"]);
+        for n in 1..=MAX_HISTORY + 5 {
+            edit(&mut history, &mut file_context, &n.to_string());
+        }
+
+        let mut undone = 0;
+        while history.undo(&mut file_context).unwrap().is_some() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_HISTORY);
+        // The five oldest changes were dropped, so undo stops at their result
+        assert_eq!(contents(&file_context), ["5"]);
+    }
+
+    #[test]
+    fn empty_change_is_not_recorded() {
+        let mut history = History::new();
+        history.record(Change::new("nothing", Vec::new()));
+        assert!(history.next_undo().is_none());
+    }
+
+    #[test]
+    fn between_round_trips() {
+        let cases: [(&[&str], &[&str]); 7] = [
+            (&[], &[]),
+            (&[], &["a", "b"]),
+            (&["a", "b"], &[]),
+            (&["a", "b", "c"], &["x", "y"]),
+            (&["a", "b", "c", "d"], &["a", "x", "c", "d", "e"]),
+            (&["a", "a", "b", "a"], &["b", "a", "a"]),
+            (&["a", "b", "c"], &["c", "b", "a"]),
+        ];
+
+        for (from, to) in cases {
+            let mut target = file(from);
+            let change = Change::between("restore", &target, &file(to));
+            change.apply(&mut target).unwrap();
+            assert_eq!(contents(&target), to, "{:?} -> {:?}", from, to);
+
+            change.inverse("undo").apply(&mut target).unwrap();
+            assert_eq!(contents(&target), from, "{:?} <- {:?}", from, to);
+        }
+    }
+
+    #[test]
+    fn between_equal_files_is_empty() {
+        let change = Change::between("restore", &file(&["a", "b"]), &file(&["a", "b"]));
+        assert!(change.operations.is_empty());
+    }
+
+    #[test]
+    fn inverse_reverts_change() {
+        let change = Change::new(
+            "mixed",
+            vec![
+                EditOperation::update(1, "a".into(), "A".into()),
+                EditOperation::delete(2, "b".into()),
+                EditOperation::insert(3, "x".into()),
+            ],
+        );
+        let mut file_context = file(&["a", "b", "c"]);
+        change.apply(&mut file_context).unwrap();
+        change.inverse("undo").apply(&mut file_context).unwrap();
+        assert_eq!(contents(&file_context), ["a", "b", "c"]);
+    }
+}
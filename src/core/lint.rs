@@ -0,0 +1,286 @@
+/// Non-interactive validation of .gitleaksignore files
+use crate::core::file_reader::{Encoding, FileContext, LineEnding};
+use crate::core::git::GitRepository;
+use crate::core::stale;
+use crate::error::Result;
+use crate::models::pattern::PatternType;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Severity of a lint issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found on a specific line
+#[derive(Debug, Clone)]
+pub struct LintIssue {
+    /// 1-based line number
+    pub line_number: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Lint checks to run
+#[derive(Debug, Clone)]
+pub struct LintOptions {
+    /// Check fingerprints against the working tree and git history
+    pub check_stale: bool,
+    /// Directory fingerprint paths are relative to (default: see [`stale::scan_root`])
+    pub root: Option<PathBuf>,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            check_stale: true,
+            root: None,
+        }
+    }
+}
+
+/// Lint a .gitleaksignore file
+///
+/// Checks, in line order:
+/// - encodings other than UTF-8, and bytes that cannot be decoded (error)
+/// - invalid entries (error)
+/// - duplicate fingerprints (error)
+/// - trailing whitespace (warning)
+/// - line endings differing from the rest of the file, or CR-only line endings (warning)
+/// - stale fingerprints whose file or commit no longer exists (warning)
+pub fn lint_file<P: AsRef<Path>>(file_path: P, options: LintOptions) -> Result<Vec<LintIssue>> {
+    let path = file_path.as_ref();
+    let file_context = FileContext::load_lossy(path)?;
+
+    let mut issues = Vec::new();
+
+    // gitleaks reads the file as UTF-8
+    if file_context.encoding != Encoding::Utf8 {
+        issues.push(LintIssue {
+            line_number: 1,
+            severity: Severity::Error,
+            message: format!(
+                "file is {} encoded; gitleaks only reads UTF-8",
+                file_context.encoding.name()
+            ),
+        });
+    }
+
+    // Invalid entries and duplicates
+    let duplicates = file_context.duplicates();
+    for (line_number, line) in file_context.numbered_lines() {
+        if line.is_damaged() {
+            issues.push(LintIssue {
+                line_number,
+                severity: Severity::Error,
+                message: format!("invalid {} byte sequence", file_context.encoding.name()),
+            });
+        }
+
+        if *line.pattern_type() == PatternType::Invalid {
+            issues.push(LintIssue {
+                line_number,
+                severity: Severity::Error,
+                message: "invalid entry: expected [commit:]file:rule:line".to_string(),
+            });
+        }
+
+        if duplicates.is_later_occurrence(line_number) {
+            let first = duplicates.first_occurrence(line_number).unwrap_or_default();
+            issues.push(LintIssue {
+                line_number,
+                severity: Severity::Error,
+                message: format!("duplicate fingerprint (first seen on line {})", first),
+            });
+        }
+
+        if line.content() != line.content().trim_end() {
+            issues.push(LintIssue {
+                line_number,
+                severity: Severity::Warning,
+                message: "trailing whitespace".to_string(),
+            });
+        }
+    }
+
+    // Mixed line endings
+    issues.extend(check_line_endings(&file_context));
+
+    // Stale references
+    if options.check_stale {
+        let repository = GitRepository::discover(path);
+        let root = options
+            .root
+            .unwrap_or_else(|| stale::scan_root(path, repository.as_ref()));
+        let report = stale::check_stale(&file_context.lines, repository.as_ref(), &root);
+        for (line_number, line) in file_context.numbered_lines() {
+            if !report.is_stale(line.content()) {
+                continue;
+            }
+            let Some(fingerprint) = line.pattern_type().fingerprint() else {
+                continue;
+            };
+            let message = match fingerprint.commit_hash {
+                Some(ref hash) => format!(
+                    "stale fingerprint: {} not found in commit {}",
+                    fingerprint.file_path,
+                    &hash[..7]
+                ),
+                None => format!("stale fingerprint: {} not found", fingerprint.file_path),
+            };
+            issues.push(LintIssue {
+                line_number,
+                severity: Severity::Warning,
+                message,
+            });
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.line_number);
+    Ok(issues)
+}
+
+/// Report lines whose ending differs from the most common ending in the file
+///
+/// A stray `\r` inside a line usually comes from a legacy CR line ending mixed with
+/// `\n` line endings; CR-only files are not split into lines by gitleaks at all.
+fn check_line_endings(file_context: &FileContext) -> Vec<LintIssue> {
+    let dominant = file_context.line_ending_format;
+    let mut issues = Vec::new();
+
+    if dominant == LineEnding::CR {
+        issues.push(LintIssue {
+            line_number: 1,
+            severity: Severity::Warning,
+            message: "CR line endings are not recognized by gitleaks".to_string(),
+        });
+    }
+
+    for (line_number, line) in file_context.numbered_lines() {
+        // The last line has no line ending of its own without a final newline
+        let terminated = line_number < file_context.total_lines || file_context.final_newline;
+
+        if terminated && line.line_ending != dominant {
+            issues.push(LintIssue {
+                line_number,
+                severity: Severity::Warning,
+                message: format!(
+                    "{:?} line ending in a file using {:?}",
+                    line.line_ending, dominant
+                ),
+            });
+        }
+
+        if line.content().contains('\r') {
+            issues.push(LintIssue {
+                line_number,
+                severity: Severity::Warning,
+                message: "carriage return (CR line ending) inside line".to_string(),
+            });
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Write `content` as the ignore file of a directory holding `src/a.go` and
+    /// `src/b.go`, and lint it against that directory
+    fn lint(content: &str) -> Vec<(usize, Severity, String)> {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/a.go"), "").unwrap();
+        fs::write(dir.path().join("src/b.go"), "").unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        fs::write(&path, content).unwrap();
+
+        let options = LintOptions {
+            check_stale: true,
+            root: Some(dir.path().to_path_buf()),
+        };
+        lint_file(&path, options)
+            .unwrap()
+            .into_iter()
+            .map(|issue| (issue.line_number, issue.severity, issue.message))
+            .collect()
+    }
+
+    #[test]
+    fn clean_file_has_no_issues() {
+        assert!(lint("# comment\n\nsrc/a.go:rule:1\nsrc/b.go:rule:2\n").is_empty());
+    }
+
+    #[test]
+    fn issues_are_reported_in_line_order() {
+        let issues = lint(concat!(
+            "# comment\n",
+            "src/a.go:rule:1\n",
+            "src/a.go:rule:1\n",
+            "src/b.go:rule:2  \n",
+            "not an entry\n",
+            "src/a.go:rule:3\r\n",
+            "missing.go:rule:4\n",
+        ));
+
+        let summary: Vec<(usize, Severity)> =
+            issues.iter().map(|(line, severity, _)| (*line, *severity)).collect();
+        assert_eq!(
+            summary,
+            [
+                (3, Severity::Error),
+                (4, Severity::Warning),
+                (5, Severity::Error),
+                (6, Severity::Warning),
+                (7, Severity::Warning),
+            ]
+        );
+        assert_eq!(issues[0].2, "duplicate fingerprint (first seen on line 2)");
+        assert_eq!(issues[1].2, "trailing whitespace");
+        assert!(issues[2].2.starts_with("invalid entry"));
+        assert!(issues[3].2.contains("line ending"));
+        assert_eq!(issues[4].2, "stale fingerprint: missing.go not found");
+    }
+
+    #[test]
+    fn last_line_without_newline_is_not_a_mixed_ending() {
+        assert!(lint("src/a.go:rule:1\r\nsrc/b.go:rule:2\r\nsrc/a.go:rule:3").is_empty());
+    }
+
+    #[test]
+    fn cr_only_line_endings_are_reported() {
+        let issues = lint("src/a.go:rule:1\rsrc/b.go:rule:2\r");
+        assert!(issues
+            .iter()
+            .any(|(line, severity, message)| *line == 1
+                && *severity == Severity::Warning
+                && message.contains("not recognized")));
+    }
+
+    #[test]
+    fn stale_check_can_be_disabled() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        fs::write(&path, "missing.go:rule:1\n").unwrap();
+        let options = LintOptions {
+            check_stale: false,
+            root: Some(dir.path().to_path_buf()),
+        };
+        assert!(lint_file(&path, options).unwrap().is_empty());
+    }
+}
@@ -2,4 +2,17 @@
 ///
 /// This module contains the file operations, parsing, and editing logic.
 pub mod backup;
+pub mod config;
+pub mod diff;
+pub mod duplicates;
 pub mod file_reader;
+pub mod filter;
+pub mod git;
+pub mod highlight;
+pub mod history;
+pub mod lint;
+pub mod preview;
+pub mod report;
+pub mod search;
+pub mod stale;
+pub mod watcher;
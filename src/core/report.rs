@@ -0,0 +1,221 @@
+/// Gitleaks JSON report loading
+use crate::error::{GliError, Result};
+use serde::Deserialize;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// A single finding from a `gitleaks detect --report-format json` report
+///
+/// Only the fields gli-editor uses are deserialized; unknown fields are ignored.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Finding {
+    /// Rule that produced the finding (e.g. `aws-access-token`)
+    #[serde(rename = "RuleID")]
+    pub rule_id: String,
+    /// Line of the match (1-based)
+    #[serde(default)]
+    pub start_line: usize,
+    /// Last line of the match (1-based)
+    #[serde(default)]
+    pub end_line: usize,
+    /// Column of the match start, as reported by gitleaks (see [`Finding::match_ranges`])
+    #[serde(default)]
+    pub start_column: usize,
+    /// Column of the match end, as reported by gitleaks
+    #[serde(default)]
+    pub end_column: usize,
+    /// Text matched by the rule
+    #[serde(rename = "Match", default)]
+    pub matched: String,
+    /// The secret within `matched`
+    #[serde(default)]
+    pub secret: String,
+    /// File the finding was reported in
+    pub file: String,
+    /// Commit hash (empty for `--no-git` scans)
+    #[serde(default)]
+    pub commit: String,
+    /// Fingerprint to add to .gitleaksignore
+    pub fingerprint: String,
+}
+
+impl Finding {
+    /// Byte ranges of the matched text in the lines of the scanned file, as
+    /// `(line_number, range)` pairs (one per line for multi-line matches)
+    ///
+    /// Gitleaks columns are byte offsets counted from the preceding newline, so they are
+    /// one too high on every line but the first of the file: the start column is 1-based
+    /// (2-based after line 1) and the end column inclusive (exclusive after line 1).
+    /// Columns counting characters instead of bytes are accepted as well. Columns that do
+    /// not select `Match` (e.g. because the file changed) are ignored and
+    /// `Match` is searched for on the reported lines instead. Returns an empty list if
+    /// neither locates the match.
+    pub fn match_ranges<S: AsRef<str>>(&self, lines: &[S]) -> Vec<(usize, Range<usize>)> {
+        self.locate(lines, false)
+    }
+
+    /// Byte ranges of the secret within the match, like [`Finding::match_ranges`]
+    ///
+    /// Falls back to the whole match if `Secret` is not part of it.
+    pub fn secret_ranges<S: AsRef<str>>(&self, lines: &[S]) -> Vec<(usize, Range<usize>)> {
+        self.locate(lines, true)
+    }
+
+    fn locate<S: AsRef<str>>(&self, lines: &[S], secret_only: bool) -> Vec<(usize, Range<usize>)> {
+        let first = self.start_line;
+        if first == 0 || first > lines.len() {
+            return Vec::new();
+        }
+
+        // The reported lines, extended to fit a multi-line `Match`
+        let match_lines = first + self.matched.matches('\n').count();
+        let last = self.end_line.max(match_lines).min(lines.len());
+        let window = lines[first - 1..last]
+            .iter()
+            .map(|line| line.as_ref())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let end_line = self.end_line.clamp(first, last);
+        let end_line_offset: usize = lines[first - 1..end_line - 1]
+            .iter()
+            .map(|line| line.as_ref().len() + 1)
+            .sum();
+
+        // Gitleaks reports byte columns; columns counting characters (from other tools or
+        // older reports) are converted with char_indices so that multi-byte text before
+        // the match is not counted several times
+        let start_column = self.start_column.saturating_sub(if first == 1 { 1 } else { 2 });
+        let end_column = self.end_column.saturating_sub(usize::from(end_line > 1));
+        let byte_range = start_column..end_line_offset + end_column;
+        let char_range = char_offset(lines[first - 1].as_ref(), start_column)
+            .zip(char_offset(lines[end_line - 1].as_ref(), end_column))
+            .map(|(start, end)| start..end_line_offset + end);
+
+        let selects_match = |range: &Range<usize>| {
+            window.get(range.clone()).is_some_and(|text| {
+                !text.is_empty() && (self.matched.is_empty() || text == self.matched)
+            })
+        };
+        let from_columns = (self.start_column > 0 && self.end_column > 0)
+            .then(|| [Some(byte_range), char_range].into_iter().flatten().find(selects_match))
+            .flatten();
+
+        let range = from_columns.or_else(|| {
+            (!self.matched.is_empty())
+                .then(|| window.find(&self.matched))
+                .flatten()
+                .map(|start| start..start + self.matched.len())
+        });
+        let Some(mut range) = range else {
+            return Vec::new();
+        };
+
+        if secret_only && !self.secret.is_empty() {
+            if let Some(offset) = window[range.clone()].find(&self.secret) {
+                range = range.start + offset..range.start + offset + self.secret.len();
+            }
+        }
+
+        // Split the range at line boundaries
+        let mut ranges = Vec::new();
+        let mut line_start = 0;
+        for (idx, line) in lines[first - 1..last].iter().enumerate() {
+            let line_end = line_start + line.as_ref().len();
+            let start = range.start.max(line_start);
+            let end = range.end.min(line_end);
+            if start < end {
+                ranges.push((first + idx, start - line_start..end - line_start));
+            }
+            line_start = line_end + 1;
+        }
+        ranges
+    }
+}
+
+/// Byte offset of the character at `column` (0-based) in `line`; the line length for the
+/// column just past the last character
+fn char_offset(line: &str, column: usize) -> Option<usize> {
+    line.char_indices()
+        .map(|(offset, _)| offset)
+        .chain([line.len()])
+        .nth(column)
+}
+
+/// Load findings from a gitleaks JSON report
+pub fn load_report<P: AsRef<Path>>(report_path: P) -> Result<Vec<Finding>> {
+    let path = report_path.as_ref();
+
+    if !path.exists() {
+        return Err(GliError::FileNotFound(path.display().to_string()));
+    }
+
+    let content = fs::read_to_string(path)?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| GliError::InvalidReport(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(line: usize, columns: (usize, usize), matched: &str, secret: &str) -> Finding {
+        Finding {
+            rule_id: "generic-api-key".to_string(),
+            start_line: line,
+            end_line: line,
+            start_column: columns.0,
+            end_column: columns.1,
+            matched: matched.to_string(),
+            secret: secret.to_string(),
+            file: "config.py".to_string(),
+            commit: String::new(),
+            fingerprint: format!("config.py:generic-api-key:{}", line),
+        }
+    }
+
+    // The secret appears twice, so only the columns select the second occurrence
+    const LINES: [&str; 2] = ["# réglages ☃", "clé = \"s3cr3t\" ä token=\"s3cr3t\""];
+
+    #[test]
+    fn byte_columns_after_non_ascii_text() {
+        // Byte 26 of line 2; columns are 2-based after line 1
+        let finding = finding(2, (28, 33), "s3cr3t", "");
+        assert_eq!(finding.match_ranges(&LINES), [(2, 26..32)]);
+        assert_eq!(&LINES[1][26..32], "s3cr3t");
+    }
+
+    #[test]
+    fn char_columns_after_non_ascii_text() {
+        // Character 24 of line 2 (byte 26)
+        let finding = finding(2, (26, 31), "s3cr3t", "");
+        assert_eq!(finding.match_ranges(&LINES), [(2, 26..32)]);
+    }
+
+    #[test]
+    fn first_line_columns_with_multibyte_prefix() {
+        let lines = ["ключ=abc123 abc123", "other"];
+        let by_bytes = finding(1, (17, 22), "abc123", "");
+        assert_eq!(by_bytes.match_ranges(&lines), [(1, 16..22)]);
+
+        let by_chars = finding(1, (13, 18), "abc123", "");
+        assert_eq!(by_chars.match_ranges(&lines), [(1, 16..22)]);
+    }
+
+    #[test]
+    fn secret_within_match_after_non_ascii_text() {
+        let finding = finding(2, (21, 33), "token=\"s3cr3t", "s3cr3t");
+        assert_eq!(finding.match_ranges(&LINES), [(2, 19..32)]);
+        assert_eq!(finding.secret_ranges(&LINES), [(2, 26..32)]);
+    }
+
+    #[test]
+    fn columns_inside_a_character_fall_back_to_search() {
+        // Byte 3 is inside "é"; the first occurrence is found by searching instead
+        let finding = finding(2, (5, 10), "s3cr3t", "");
+        assert_eq!(finding.match_ranges(&LINES), [(2, 8..14)]);
+    }
+}
@@ -0,0 +1,116 @@
+/// Free-text search over ignore file entries
+use crate::error::{GliError, Result};
+use regex::{Regex, RegexBuilder};
+
+/// Search query as typed by the user
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    /// Text or regular expression to search for
+    pub pattern: String,
+    /// Ignore case when matching
+    pub case_insensitive: bool,
+    /// Treat pattern as a regular expression instead of literal text
+    pub regex: bool,
+}
+
+/// Compiled search ready for matching
+#[derive(Debug, Clone)]
+pub struct Search {
+    pub query: SearchQuery,
+    matcher: Regex,
+}
+
+impl Search {
+    /// Compile a query
+    ///
+    /// Returns None for an empty pattern.
+    pub fn compile(query: SearchQuery) -> Result<Option<Self>> {
+        if query.pattern.is_empty() {
+            return Ok(None);
+        }
+
+        let pattern = if query.regex {
+            query.pattern.clone()
+        } else {
+            regex::escape(&query.pattern)
+        };
+
+        let matcher = RegexBuilder::new(&pattern)
+            .case_insensitive(query.case_insensitive)
+            .build()
+            .map_err(|e| GliError::InvalidPattern(e.to_string()))?;
+
+        Ok(Some(Self { query, matcher }))
+    }
+
+    /// Check whether a line contains a match
+    pub fn is_match(&self, text: &str) -> bool {
+        self.matcher.is_match(text)
+    }
+
+    /// Byte ranges of all non-empty matches in `text`
+    pub fn find_ranges(&self, text: &str) -> Vec<(usize, usize)> {
+        self.matcher
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(pattern: &str, case_insensitive: bool, regex: bool) -> Search {
+        Search::compile(SearchQuery {
+            pattern: pattern.to_string(),
+            case_insensitive,
+            regex,
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn empty_pattern_is_no_search() {
+        assert!(Search::compile(SearchQuery::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn literal_text_escapes_regex_syntax() {
+        let search = search("a.go", false, false);
+        assert!(search.is_match("src/a.go:rule:1"));
+        assert!(!search.is_match("src/abgo:rule:1"));
+    }
+
+    #[test]
+    fn case_sensitivity() {
+        assert!(!search("AWS", false, false).is_match("aws-access-token"));
+        assert!(search("AWS", true, false).is_match("aws-access-token"));
+    }
+
+    #[test]
+    fn regex_matches_and_ranges() {
+        let search = search(r"rule:\d+", false, true);
+        assert!(search.is_match("src/a.go:rule:12"));
+        assert_eq!(search.find_ranges("a:rule:1 b:rule:23"), [(2, 8), (11, 18)]);
+        // Ranges are byte offsets, also after multibyte text
+        assert_eq!(search.find_ranges("ключ:rule:7"), [(9, 15)]);
+    }
+
+    #[test]
+    fn empty_matches_are_skipped() {
+        assert!(search("x*", false, true).find_ranges("abc").is_empty());
+    }
+
+    #[test]
+    fn invalid_regex_is_an_error() {
+        let result = Search::compile(SearchQuery {
+            pattern: "(".to_string(),
+            case_insensitive: false,
+            regex: true,
+        });
+        assert!(matches!(result, Err(GliError::InvalidPattern(_))));
+    }
+}
@@ -0,0 +1,216 @@
+/// Stale fingerprint detection
+use crate::core::git::GitRepository;
+use crate::models::fingerprint::Fingerprint;
+use crate::models::line::Line;
+use crate::models::pattern::PatternType;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Result of a stale-entry validation pass
+#[derive(Debug, Clone, Default)]
+pub struct StaleReport {
+    /// Trimmed content of fingerprints whose file or commit no longer exists
+    pub stale: HashSet<String>,
+    /// Number of fingerprints checked
+    pub checked: usize,
+    /// Number of commit fingerprints that could not be verified (not in a git repository)
+    pub unchecked: usize,
+}
+
+impl StaleReport {
+    /// Check whether a line is a stale fingerprint
+    pub fn is_stale(&self, content: &str) -> bool {
+        self.stale.contains(content.trim())
+    }
+}
+
+/// Directory that relative fingerprint paths are resolved against
+///
+/// Gitleaks reports paths relative to the scanned directory: the working tree of the
+/// repository containing the ignore file, or the ignore file's own directory outside a
+/// repository. The result does not depend on the process's working directory.
+pub fn scan_root(ignore_file: &Path, repository: Option<&GitRepository>) -> PathBuf {
+    let dir = match repository.and_then(|repository| repository.workdir()) {
+        Some(workdir) => workdir,
+        None => match ignore_file.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        },
+    };
+    dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf())
+}
+
+/// Resolve a fingerprint file path against the scan root (absolute paths are kept)
+pub fn resolve_source_path(root: &Path, file_path: &str) -> PathBuf {
+    root.join(file_path)
+}
+
+/// Path of the file on disk for a fingerprint path
+///
+/// Findings inside archives use `archive.tar.gz:inner.tar:file.env`; only the outer
+/// archive exists in the working tree or commit.
+fn outer_path(file_path: &str) -> &str {
+    file_path.split(':').next().unwrap_or(file_path)
+}
+
+/// Find fingerprints whose referenced file or commit no longer exists
+///
+/// - Entries without a commit are checked against the working tree, relative to
+///   `root` (see [`scan_root`]).
+/// - Entries with a commit are looked up in `repository`; without a repository they
+///   are counted as unchecked.
+pub fn check_stale<'a>(
+    lines: impl IntoIterator<Item = &'a Line>,
+    repository: Option<&GitRepository>,
+    root: &Path,
+) -> StaleReport {
+    let mut report = StaleReport::default();
+
+    for line in lines {
+        let PatternType::Fingerprint(Fingerprint {
+            commit_hash,
+            file_path,
+            ..
+        }) = line.pattern_type()
+        else {
+            continue;
+        };
+
+        report.checked += 1;
+        let path = outer_path(file_path);
+
+        let exists = match (commit_hash, repository) {
+            (Some(hash), Some(repository)) => repository.contains(hash, path),
+            (Some(_), None) => {
+                report.unchecked += 1;
+                continue;
+            }
+            (None, _) => resolve_source_path(root, path).exists(),
+        };
+
+        if !exists {
+            report.stale.insert(line.content().trim().to_string());
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    /// Run git in `dir` and return its trimmed output
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// Repository with `src/a.go` and `src/b.go` committed, and `src/b.go` since deleted
+    /// from the working tree; returns the directory and the commit
+    fn repository() -> (TempDir, String) {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/a.go"), "package a\n").unwrap();
+        fs::write(dir.path().join("src/b.go"), "package b\n").unwrap();
+        git(dir.path(), &["init", "-q"]);
+        git(dir.path(), &["add", "-A"]);
+        git(dir.path(), &["commit", "-q", "-m", "initial"]);
+        fs::remove_file(dir.path().join("src/b.go")).unwrap();
+        let commit = git(dir.path(), &["rev-parse", "HEAD"]);
+        (dir, commit)
+    }
+
+    fn lines(entries: &[String]) -> Vec<Line> {
+        entries.iter().map(|entry| Line::new(entry.clone())).collect()
+    }
+
+    #[test]
+    fn stale_entries_in_working_tree_and_commits() {
+        let (dir, commit) = repository();
+        let repository = GitRepository::discover(dir.path()).unwrap();
+        let root = scan_root(&dir.path().join(".gitleaksignore"), Some(&repository));
+        let unknown = "0".repeat(40);
+
+        let entries = [
+            "# comment".to_string(),
+            "src/a.go:rule:1".to_string(),
+            "src/b.go:rule:2".to_string(),
+            format!("{}:src/b.go:rule:2", commit),
+            format!("{}:src/a.go:inner.env:rule:1", commit),
+            format!("{}:src/c.go:rule:3", commit),
+            format!("{}:src/a.go:rule:1", unknown),
+        ];
+        let report = check_stale(&lines(&entries), Some(&repository), &root);
+
+        assert_eq!(report.checked, 6);
+        assert_eq!(report.unchecked, 0);
+        let stale: Vec<bool> = entries.iter().map(|entry| report.is_stale(entry)).collect();
+        assert_eq!(stale, [false, false, true, false, false, true, true]);
+    }
+
+    #[test]
+    fn scan_root_is_the_repository_toplevel() {
+        let (dir, _) = repository();
+        let ignore_file = dir.path().join("src/.gitleaksignore");
+        let repository = GitRepository::discover(&ignore_file);
+
+        let root = scan_root(&ignore_file, repository.as_ref());
+        assert_eq!(root, dir.path().canonicalize().unwrap());
+        assert!(resolve_source_path(&root, "src/a.go").exists());
+    }
+
+    #[test]
+    fn scan_root_outside_a_repository_is_the_ignore_file_directory() {
+        let dir = TempDir::new().unwrap();
+        let ignore_file = dir.path().join("nested/.gitleaksignore");
+        fs::create_dir(dir.path().join("nested")).unwrap();
+
+        let root = scan_root(&ignore_file, None);
+        assert_eq!(root, dir.path().join("nested").canonicalize().unwrap());
+
+        // A bare file name is relative to the working directory
+        let root = scan_root(Path::new(".gitleaksignore"), None);
+        assert_eq!(root, std::env::current_dir().unwrap().canonicalize().unwrap());
+    }
+
+    #[test]
+    fn absolute_fingerprint_paths_are_kept() {
+        let root = Path::new("/repo");
+        assert_eq!(resolve_source_path(root, "src/a.go"), Path::new("/repo/src/a.go"));
+        assert_eq!(resolve_source_path(root, "/etc/app.env"), Path::new("/etc/app.env"));
+    }
+
+    #[test]
+    fn commit_entries_are_unchecked_outside_a_repository() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.go"), "").unwrap();
+        let entries = [
+            "a.go:rule:1".to_string(),
+            "gone.go:rule:1".to_string(),
+            format!("{}:gone.go:rule:1", "a".repeat(40)),
+        ];
+        let report = check_stale(&lines(&entries), None, dir.path());
+
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.unchecked, 1);
+        assert!(!report.is_stale(&entries[0]));
+        assert!(report.is_stale(&entries[1]));
+        assert!(!report.is_stale(&entries[2]));
+    }
+}
@@ -0,0 +1,116 @@
+/// Watching the ignore file for changes made by other processes
+use notify::{Config, Event, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+/// Scan interval of the polling fallback
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Notifies about filesystem events affecting a single file
+///
+/// The parent directory is watched rather than the file itself, so that atomic
+/// replacements (write to a temp file, then rename over the original) are seen too.
+pub struct FileWatcher {
+    /// Kept alive for as long as events are wanted
+    _watcher: Box<dyn Watcher>,
+    events: Receiver<notify::Result<Event>>,
+    file_name: Option<OsString>,
+}
+
+impl FileWatcher {
+    /// Start watching a file with inotify (or the platform equivalent), falling back to
+    /// polling if native watching is unavailable
+    pub fn new(file_path: &Path) -> notify::Result<Self> {
+        let dir = match file_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+
+        let (sender, events) = mpsc::channel();
+        let watcher = Self::native(dir, sender.clone()).or_else(|_| Self::polling(dir, sender))?;
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            file_name: file_path.file_name().map(|name| name.to_os_string()),
+        })
+    }
+
+    fn native(
+        dir: &Path,
+        sender: Sender<notify::Result<Event>>,
+    ) -> notify::Result<Box<dyn Watcher>> {
+        let mut watcher = RecommendedWatcher::new(sender, Config::default())?;
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(Box::new(watcher))
+    }
+
+    fn polling(
+        dir: &Path,
+        sender: Sender<notify::Result<Event>>,
+    ) -> notify::Result<Box<dyn Watcher>> {
+        let config = Config::default().with_poll_interval(POLL_INTERVAL);
+        let mut watcher = PollWatcher::new(sender, config)?;
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(Box::new(watcher))
+    }
+
+    /// Check whether the file may have changed since the last call
+    ///
+    /// Drains all pending events. Watcher errors count as a possible change; callers
+    /// confirm with the file's modification time.
+    pub fn has_changed(&self) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.events.try_recv() {
+            changed |= match event {
+                Ok(event) => event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name() == self.file_name.as_deref()),
+                Err(_) => true,
+            };
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::thread;
+    use std::time::Instant;
+    use tempfile::TempDir;
+
+    /// Wait for the watcher to report a change
+    fn wait_for_change(watcher: &FileWatcher) -> bool {
+        let deadline = Instant::now() + POLL_INTERVAL * 5;
+        while Instant::now() < deadline {
+            if watcher.has_changed() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    #[test]
+    fn atomic_replacement_is_a_change() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        fs::write(&path, "a.go:rule:1\n").unwrap();
+        let watcher = FileWatcher::new(&path).unwrap();
+
+        // Other files in the directory are not reported
+        fs::write(dir.path().join("other"), "x").unwrap();
+        thread::sleep(Duration::from_millis(200));
+        assert!(!watcher.has_changed());
+
+        let temp = dir.path().join(".gitleaksignore.tmp");
+        fs::write(&temp, "b.go:rule:2\n").unwrap();
+        fs::rename(&temp, &path).unwrap();
+        assert!(wait_for_change(&watcher));
+    }
+}
@@ -17,6 +17,18 @@ pub enum GliError {
     WriteFailure(String),
     /// Invalid command-line arguments
     InvalidArguments(String),
+    /// Gitleaks report could not be parsed
+    InvalidReport(String),
+    /// Search pattern could not be compiled
+    InvalidPattern(String),
+    /// Field filter expression could not be parsed
+    InvalidFilter(String),
+    /// Text is not a valid gitleaks fingerprint
+    InvalidFingerprint(String),
+    /// Configuration file could not be parsed or holds invalid values
+    InvalidConfig(String),
+    /// No backup with this id exists for the file
+    BackupNotFound(String),
     /// I/O error occurred
     IoError(std::io::Error),
 }
@@ -49,6 +61,24 @@ impl fmt::Display for GliError {
             GliError::InvalidArguments(msg) => {
                 write!(f, "Error: Invalid arguments: {}", msg)
             }
+            GliError::InvalidReport(msg) => {
+                write!(f, "Error: Invalid gitleaks report: {}", msg)
+            }
+            GliError::InvalidPattern(msg) => {
+                write!(f, "Error: Invalid search pattern: {}", msg)
+            }
+            GliError::InvalidFilter(msg) => {
+                write!(f, "Error: Invalid filter: {}", msg)
+            }
+            GliError::InvalidFingerprint(text) => {
+                write!(f, "Error: Invalid fingerprint: {}\n\nExpected: [commit:]file:rule:line", text)
+            }
+            GliError::InvalidConfig(msg) => {
+                write!(f, "Error: Invalid configuration: {}", msg)
+            }
+            GliError::BackupNotFound(id) => {
+                write!(f, "Error: Backup not found: {}\n\nSuggestion: List backups with:\n  gli-editor backups list", id)
+            }
             GliError::IoError(err) => {
                 write!(f, "I/O Error: {}", err)
             }
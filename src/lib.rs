@@ -0,0 +1,24 @@
+//! gli-editor library: parsing, querying and safely editing .gitleaksignore files
+//!
+//! The `gli-editor` binary (TUI and `lint` subcommand) is built on top of this crate.
+//! The most commonly used types are re-exported at the crate root:
+//!
+//! - [`FileContext`] loads a file (`load_lossy` also opens files with invalid bytes),
+//!   gives access to its [`Line`]s, modifies them (`update_line`, `insert_line`,
+//!   `delete_line`, `append_line`) and saves them with `write_atomic`, preserving
+//!   encoding and line endings
+//! - [`PatternType`] classifies a line; [`Fingerprint`] parses (`FromStr`) and formats
+//!   (`Display`) a single gitleaks fingerprint
+//! - [`BackupManager`] backs files up before modifications, into a backup directory with
+//!   a manifest of the change that produced each backup, and lists and restores them
+
+pub mod core;
+pub mod error;
+pub mod models;
+
+pub use crate::core::backup::BackupManager;
+pub use crate::core::file_reader::{Encoding, FileContext, LineEnding};
+pub use crate::error::{GliError, Result};
+pub use crate::models::fingerprint::Fingerprint;
+pub use crate::models::line::Line;
+pub use crate::models::pattern::PatternType;
@@ -2,15 +2,26 @@
 ///
 /// This is the main entry point for the application.
 mod app;
-mod core;
-mod error;
-mod models;
 mod ui;
 
 use app::App;
-use clap::Parser;
-use error::{GliError, Result};
-use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand};
+use gli_editor::core::backup::{BackupManager, BackupOptions};
+use gli_editor::core::config::{BackupConfig, Config, PreviewConfig, ViewConfig};
+use gli_editor::core::diff::{self, DiffKind};
+use gli_editor::core::file_reader::FileContext;
+use gli_editor::core::lint::{self, LintOptions, Severity};
+use gli_editor::error::{GliError, Result};
+use std::path::{Path, PathBuf};
+
+/// Exit code when lint finds errors (or warnings with --strict)
+const EXIT_LINT_FAILED: i32 = 5;
+
+/// Unchanged lines shown around each difference by `backups diff`
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Default number of context lines around the target line of `--lines`
+const DEFAULT_CONTEXT: usize = 3;
 
 /// Terminal editor for .gitleaksignore files
 #[derive(Parser, Debug)]
@@ -26,13 +37,114 @@ struct Cli {
     #[arg(short, long)]
     lines: Option<String>,
 
-    /// Number of context lines around target line
-    #[arg(short = 'C', long, default_value = "3")]
-    context: usize,
+    /// Number of context lines around target line [default: 3]
+    #[arg(short = 'C', long)]
+    context: Option<usize>,
 
     /// Launch in read-only mode (disable editing)
     #[arg(short, long)]
     read_only: bool,
+
+    /// Gitleaks JSON report to pick findings from
+    #[arg(long, value_name = "PATH")]
+    report: Option<PathBuf>,
+
+    /// Directory fingerprint paths are relative to (default: repository root)
+    #[arg(long, value_name = "DIR")]
+    root: Option<PathBuf>,
+
+    /// Never reveal masked secrets in the preview
+    #[arg(long)]
+    no_reveal: bool,
+
+    #[command(flatten)]
+    backup: BackupArgs,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Backup location and retention
+#[derive(Args, Debug)]
+struct BackupArgs {
+    /// Directory backups are written to (default: .git/gli-editor/backups)
+    #[arg(long, value_name = "DIR")]
+    backup_dir: Option<PathBuf>,
+
+    /// Number of backups kept per file, 0 keeps all [default: 5]
+    #[arg(long, value_name = "N")]
+    keep_backups: Option<usize>,
+
+    /// Remove backups older than this many days
+    #[arg(long, value_name = "DAYS")]
+    backup_max_age: Option<u64>,
+}
+
+impl BackupArgs {
+    /// The flags as a config layer
+    fn config(self) -> BackupConfig {
+        BackupConfig {
+            dir: self.backup_dir,
+            keep: self.keep_backups,
+            max_age_days: self.backup_max_age,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check a .gitleaksignore file without launching the TUI
+    ///
+    /// Prints one `file:line: severity: message` line per issue and exits with
+    /// status 5 if any errors were found.
+    Lint {
+        /// Path to .gitleaksignore file
+        #[arg(short, long, default_value = "./.gitleaksignore")]
+        file: PathBuf,
+
+        /// Treat warnings as errors
+        #[arg(long)]
+        strict: bool,
+
+        /// Skip checking fingerprints against the working tree and git history
+        #[arg(long)]
+        no_stale: bool,
+
+        /// Directory fingerprint paths are relative to (default: repository root)
+        #[arg(long, value_name = "DIR")]
+        root: Option<PathBuf>,
+    },
+    /// List, diff and restore the backups of a .gitleaksignore file
+    ///
+    /// Backups are taken before every change the editor writes; restoring one backs up
+    /// the current file first.
+    Backups {
+        /// Path to .gitleaksignore file
+        #[arg(short, long, default_value = "./.gitleaksignore")]
+        file: PathBuf,
+
+        #[command(flatten)]
+        backup: BackupArgs,
+
+        #[command(subcommand)]
+        action: BackupsAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BackupsAction {
+    /// List backups, newest first
+    List,
+    /// Show what restoring a backup would change in the current file
+    Diff {
+        /// Backup id (from `backups list`)
+        id: String,
+    },
+    /// Restore a backup, backing up the current file first
+    Restore {
+        /// Backup id (from `backups list`)
+        id: String,
+    },
 }
 
 /// Line specification for viewing
@@ -137,20 +249,196 @@ impl LineSpec {
     }
 }
 
+/// Check that the root directory (`--root` or `root` in a config file) is a directory
+/// and make it absolute
+fn resolve_root(root: Option<PathBuf>) -> Result<Option<PathBuf>> {
+    let Some(root) = root else {
+        return Ok(None);
+    };
+    if !root.is_dir() {
+        return Err(GliError::InvalidArguments(format!(
+            "root {} is not a directory",
+            root.display()
+        )));
+    }
+    Ok(Some(root.canonicalize()?))
+}
+
+/// Run the lint subcommand and return the process exit code
+fn run_lint(file: &Path, strict: bool, no_stale: bool, root: Option<PathBuf>) -> Result<i32> {
+    let options = LintOptions {
+        check_stale: !no_stale,
+        root,
+    };
+    let issues = lint::lint_file(file, options)?;
+
+    for issue in &issues {
+        println!(
+            "{}:{}: {}: {}",
+            file.display(),
+            issue.line_number,
+            issue.severity,
+            issue.message
+        );
+    }
+
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    let warnings = issues.len() - errors;
+    eprintln!("{}: {} error(s), {} warning(s)", file.display(), errors, warnings);
+
+    if errors > 0 || (strict && warnings > 0) {
+        Ok(EXIT_LINT_FAILED)
+    } else {
+        Ok(0)
+    }
+}
+
+/// Run the backups subcommand
+fn run_backups(file: &Path, options: BackupOptions, action: BackupsAction) -> Result<()> {
+    let manager = BackupManager::with_options(options);
+
+    match action {
+        BackupsAction::List => {
+            let entries = manager.list_backups(file)?;
+            for entry in &entries {
+                println!("{}  {}  {}", entry.id, entry.timestamp(), entry.operation);
+            }
+            eprintln!(
+                "{}: {} backup(s) in {}",
+                file.display(),
+                entries.len(),
+                manager.backup_dir(file).display()
+            );
+        }
+        BackupsAction::Diff { id } => {
+            let entry = manager.find_backup(file, &id)?;
+            let current = FileContext::load_lossy(file)?;
+            let backup = manager.load_backup(file, &entry)?;
+            let current_lines: Vec<&str> =
+                current.lines.iter().map(|l| l.content()).collect();
+            let backup_lines: Vec<&str> =
+                backup.lines.iter().map(|l| l.content()).collect();
+
+            println!("--- {} (current)", file.display());
+            println!("+++ backup {} (before: {})", entry.id, entry.operation);
+            for line in diff::diff_hunks(&current_lines, &backup_lines, DIFF_CONTEXT_LINES) {
+                match line {
+                    None => println!("@@"),
+                    Some(line) => {
+                        let marker = match line.kind {
+                            DiffKind::Equal => ' ',
+                            DiffKind::Removed => '-',
+                            DiffKind::Added => '+',
+                        };
+                        println!("{}{}", marker, line.content);
+                    }
+                }
+            }
+        }
+        BackupsAction::Restore { id } => {
+            let entry = manager.find_backup(file, &id)?;
+            let mut file_context = FileContext::load_lossy(file)?;
+            let change = manager.restore_change(&file_context, &entry)?;
+            if change.operations.is_empty() {
+                eprintln!("{}: already matches backup {}", file.display(), entry.id);
+                return Ok(());
+            }
+
+            let backup_path = manager.create_backup(file, &change.description)?;
+            change.apply(&mut file_context)?;
+            file_context.write_atomic()?;
+            eprintln!(
+                "{}: restored backup {} (current state saved as {})",
+                file.display(),
+                entry.id,
+                backup_path.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
 fn main() -> Result<()> {
     // Parse command-line arguments
     let cli = Cli::parse();
 
+    // Non-interactive subcommands
+    match cli.command {
+        Some(Command::Lint {
+            file,
+            strict,
+            no_stale,
+            root,
+        }) => {
+            let root = root.or(Config::load(&file)?.root);
+            let code = run_lint(&file, strict, no_stale, resolve_root(root)?)?;
+            std::process::exit(code);
+        }
+        Some(Command::Backups {
+            file,
+            backup,
+            action,
+        }) => {
+            let config = Config::load(&file)?.merge(Config {
+                backup: backup.config(),
+                ..Config::default()
+            });
+            return run_backups(&file, config.backup.options(), action);
+        }
+        None => {}
+    }
+
+    // Command-line flags override the user and repository config files
+    let mut config = Config::load(&cli.file)?.merge(Config {
+        root: cli.root,
+        view: ViewConfig {
+            context: cli.context,
+            ..ViewConfig::default()
+        },
+        preview: PreviewConfig {
+            no_reveal: cli.no_reveal.then_some(true),
+            ..PreviewConfig::default()
+        },
+        backup: cli.backup.config(),
+        ..Config::default()
+    });
+    config.root = resolve_root(config.root)?;
+
     // Parse line specification
     let line_spec = if let Some(ref lines_str) = cli.lines {
-        LineSpec::parse(lines_str, cli.context)?
+        LineSpec::parse(lines_str, config.view.context.unwrap_or(DEFAULT_CONTEXT))?
     } else {
         LineSpec::All
     };
 
     // Create and run application with parsed arguments
-    let mut app = App::new(cli.file, line_spec, cli.read_only)?;
+    let mut app = App::new(cli.file, line_spec, cli.read_only, cli.report, config)?;
     app.run()?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Exit code of `gli-editor lint` on an ignore file with `content`
+    fn lint_exit_code(content: &str, strict: bool) -> i32 {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".gitleaksignore");
+        std::fs::write(&path, content).unwrap();
+        run_lint(&path, strict, true, Some(dir.path().to_path_buf())).unwrap()
+    }
+
+    #[test]
+    fn lint_exit_codes() {
+        assert_eq!(lint_exit_code("# comment\nsrc/a.go:rule:1\n", false), 0);
+        assert_eq!(lint_exit_code("not an entry\n", false), EXIT_LINT_FAILED);
+        assert_eq!(lint_exit_code("src/a.go:rule:1\nsrc/a.go:rule:1\n", false), EXIT_LINT_FAILED);
+        // Warnings only fail in strict mode
+        assert_eq!(lint_exit_code("src/a.go:rule:1 \n", false), 0);
+        assert_eq!(lint_exit_code("src/a.go:rule:1 \n", true), EXIT_LINT_FAILED);
+    }
+}
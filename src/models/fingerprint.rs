@@ -0,0 +1,150 @@
+/// Gitleaks fingerprint type
+use crate::error::GliError;
+use std::fmt;
+use std::str::FromStr;
+
+/// A gitleaks finding fingerprint: `[commit_hash:]file_path:rule_id:line_number`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    /// Commit the finding was made in (40 hex characters), absent for `--no-git` scans
+    pub commit_hash: Option<String>,
+    /// Path of the file; may contain ':' for findings inside archives
+    /// (e.g. `archive.tar.gz:inner.tar:file.env`)
+    pub file_path: String,
+    pub rule_id: String,
+    pub line_number: u32,
+}
+
+impl FromStr for Fingerprint {
+    type Err = GliError;
+
+    /// Parse a fingerprint, ignoring surrounding whitespace
+    ///
+    /// Uses hand-written parser for performance (2-5x faster than regex per research.md).
+    /// The components are split from the right so that file paths may contain ':'; a
+    /// leading component is only taken as the commit hash if it is exactly 40 hexadecimal
+    /// characters.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let invalid = || GliError::InvalidFingerprint(trimmed.to_string());
+
+        // Would be read back as a comment
+        if trimmed.starts_with('#') {
+            return Err(invalid());
+        }
+
+        // Find the last ':' for line_number
+        let (rest, line_number_str) = trimmed.rsplit_once(':').ok_or_else(invalid)?;
+        let line_number = line_number_str.parse::<u32>().map_err(|_| invalid())?;
+
+        // Find second-to-last ':' for rule_id
+        let (remaining, rule_id) = rest.rsplit_once(':').ok_or_else(invalid)?;
+
+        // Check if the first component is a commit hash (the path may contain ':' itself)
+        if let Some((potential_hash, file_path)) = remaining.split_once(':') {
+            // Check if it looks like a commit hash (40 hex chars)
+            if potential_hash.len() == 40
+                && potential_hash.chars().all(|c| c.is_ascii_hexdigit())
+                && !file_path.is_empty()
+                && !rule_id.is_empty()
+            {
+                return Ok(Self {
+                    commit_hash: Some(potential_hash.to_string()),
+                    file_path: file_path.to_string(),
+                    rule_id: rule_id.to_string(),
+                    line_number,
+                });
+            }
+        }
+
+        // No commit hash, treat remaining as file_path
+        let file_path = remaining;
+
+        // Validate that we have non-empty components
+        if file_path.is_empty() || rule_id.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            commit_hash: None,
+            file_path: file_path.to_string(),
+            rule_id: rule_id.to_string(),
+            line_number,
+        })
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref hash) = self.commit_hash {
+            write!(f, "{}:", hash)?;
+        }
+        write!(f, "{}:{}:{}", self.file_path, self.rule_id, self.line_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "cd5226711335c68be1e720b318b7bc3135a30eb2";
+
+    fn parse(text: &str) -> Fingerprint {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn round_trip_without_commit() {
+        let fingerprint = parse("src/config.go:aws-access-token:42");
+        assert_eq!(fingerprint.commit_hash, None);
+        assert_eq!(fingerprint.file_path, "src/config.go");
+        assert_eq!(fingerprint.rule_id, "aws-access-token");
+        assert_eq!(fingerprint.line_number, 42);
+        assert_eq!(fingerprint.to_string(), "src/config.go:aws-access-token:42");
+    }
+
+    #[test]
+    fn round_trip_with_commit() {
+        let text = format!("{}:src/config.go:aws-access-token:42", HASH);
+        let fingerprint = parse(&format!("  {}\t", text));
+        assert_eq!(fingerprint.commit_hash.as_deref(), Some(HASH));
+        assert_eq!(fingerprint.file_path, "src/config.go");
+        assert_eq!(fingerprint.to_string(), text);
+    }
+
+    #[test]
+    fn paths_may_contain_colons() {
+        let text = "archive.tar.gz:inner.tar:file.env:generic-api-key:3";
+        let fingerprint = parse(text);
+        assert_eq!(fingerprint.commit_hash, None);
+        assert_eq!(fingerprint.file_path, "archive.tar.gz:inner.tar:file.env");
+        assert_eq!(fingerprint.to_string(), text);
+
+        let text = format!("{}:C:/repo/a.go:rule:1", HASH);
+        let fingerprint = parse(&text);
+        assert_eq!(fingerprint.commit_hash.as_deref(), Some(HASH));
+        assert_eq!(fingerprint.file_path, "C:/repo/a.go");
+        assert_eq!(fingerprint.to_string(), text);
+    }
+
+    #[test]
+    fn short_hash_is_part_of_the_path() {
+        let fingerprint = parse("cd52267:src/a.go:rule:1");
+        assert_eq!(fingerprint.commit_hash, None);
+        assert_eq!(fingerprint.file_path, "cd52267:src/a.go");
+    }
+
+    #[test]
+    fn comments_are_rejected() {
+        assert!("#src/a.go:rule:1".parse::<Fingerprint>().is_err());
+        assert!("  # src/a.go:rule:1".parse::<Fingerprint>().is_err());
+        assert!(format!("#{}:src/a.go:rule:1", HASH).parse::<Fingerprint>().is_err());
+    }
+
+    #[test]
+    fn malformed_entries_are_rejected() {
+        for text in ["", "src/a.go", "src/a.go:rule", "src/a.go:rule:x", ":rule:1", "a.go::1"] {
+            assert!(text.parse::<Fingerprint>().is_err(), "{:?}", text);
+        }
+    }
+}
@@ -1,49 +1,80 @@
 /// Line data structures
+use crate::core::file_reader::LineEnding;
 use crate::models::pattern::PatternType;
+use std::sync::OnceLock;
 
 /// Represents a single line in the .gitleaksignore file
 ///
-/// Also known as IgnorePatternEntry in data-model.md
+/// Also known as IgnorePatternEntry in data-model.md. Line numbers are implicit (the
+/// position in `FileContext::lines`), so inserting or deleting lines never has to
+/// renumber the rest of the file.
 #[derive(Debug, Clone)]
 pub struct Line {
-    /// 1-based line number in file
-    pub line_number: usize,
-    /// Raw line content including any whitespace (but excluding line ending)
-    pub content: String,
-    /// Classification of the line content
-    pub pattern_type: PatternType,
+    /// Raw line content including any whitespace (but excluding line ending); private so
+    /// that changing it always goes through `set_content`, which resets `pattern_type`
+    content: String,
+    /// Classification of the line content, parsed on first use
+    pattern_type: OnceLock<PatternType>,
+    /// Line ending that terminates this line in the file
+    pub line_ending: LineEnding,
+    /// Original bytes of a line that could not be decoded (`content` then holds a lossy
+    /// copy with U+FFFD); written back unchanged until the line is edited
+    pub raw: Option<Vec<u8>>,
 }
 
 impl Line {
-    /// Create a new Line with parsed pattern type
-    pub fn new(line_number: usize, content: String) -> Self {
-        let pattern_type = PatternType::parse(&content);
+    /// Create a new Line; the pattern type is parsed lazily
+    pub fn new(content: String) -> Self {
         Self {
-            line_number,
             content,
-            pattern_type,
+            pattern_type: OnceLock::new(),
+            line_ending: LineEnding::LF,
+            raw: None,
         }
     }
+
+    /// Raw line content including any whitespace (but excluding line ending)
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Classification of the line content
+    pub fn pattern_type(&self) -> &PatternType {
+        self.pattern_type
+            .get_or_init(|| PatternType::parse(&self.content))
+    }
+
+    /// Replace the content, dropping the cached classification and any raw bytes
+    pub fn set_content(&mut self, content: String) {
+        self.content = content;
+        self.pattern_type = OnceLock::new();
+        self.raw = None;
+    }
+
+    /// Whether the line contained bytes that could not be decoded
+    pub fn is_damaged(&self) -> bool {
+        self.raw.is_some()
+    }
 }
 
-/// Represents a continuous sequence of lines for display or navigation
-#[derive(Debug, Clone)]
+/// Represents a continuous sequence of displayed rows
+///
+/// Only the bounds are stored; the lines themselves are read from the buffer when
+/// rendering.
+#[derive(Debug, Clone, Copy)]
 pub struct LineRange {
-    /// First line number (1-based, inclusive)
+    /// First row (1-based, inclusive)
     pub start_line: usize,
-    /// Last line number (1-based, inclusive)
+    /// Last row (1-based, inclusive)
     pub end_line: usize,
-    /// Lines within the range
-    pub entries: Vec<Line>,
 }
 
 impl LineRange {
     /// Create a new LineRange
-    pub fn new(start_line: usize, end_line: usize, entries: Vec<Line>) -> Self {
+    pub fn new(start_line: usize, end_line: usize) -> Self {
         Self {
             start_line,
             end_line,
-            entries,
         }
     }
 }
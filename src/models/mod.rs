@@ -1,5 +1,6 @@
 /// Data models for gli-editor
 ///
 /// This module contains the core data structures used throughout the application.
+pub mod fingerprint;
 pub mod line;
 pub mod pattern;
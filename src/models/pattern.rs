@@ -1,16 +1,12 @@
 /// Pattern type classification for .gitleaksignore entries
+use crate::models::fingerprint::Fingerprint;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PatternType {
     /// Line starting with # (comment)
     Comment,
     /// Valid gitleaks fingerprint with 3-4 components
-    Fingerprint {
-        commit_hash: Option<String>,
-        file_path: String,
-        rule_id: String,
-        line_number: u32,
-    },
+    Fingerprint(Fingerprint),
     /// Empty or whitespace-only line
     BlankLine,
     /// Malformed entry
@@ -20,8 +16,6 @@ pub enum PatternType {
 impl PatternType {
     /// Parse a line to detect its pattern type
     ///
-    /// Uses hand-written parser for performance (2-5x faster than regex per research.md)
-    ///
     /// # Pattern Detection Rules
     ///
     /// 1. BlankLine: Empty or whitespace-only
@@ -45,61 +39,17 @@ impl PatternType {
         }
 
         // Try to parse as fingerprint
-        // Format: [commit_hash:]file_path:rule_id:line_number
-        // Note: file_path can contain ':' for archives (e.g., archive.tar.gz:inner.tar:file.env)
-
-        // Find the last ':' for line_number
-        let Some(last_colon) = trimmed.rfind(':') else {
-            return PatternType::Invalid;
-        };
-
-        let line_number_str = &trimmed[last_colon + 1..];
-        let Ok(line_number) = line_number_str.parse::<u32>() else {
-            return PatternType::Invalid;
-        };
-
-        let rest = &trimmed[..last_colon];
-
-        // Find second-to-last ':' for rule_id
-        let Some(second_last_colon) = rest.rfind(':') else {
-            return PatternType::Invalid;
-        };
-
-        let rule_id = &rest[second_last_colon + 1..];
-        let remaining = &rest[..second_last_colon];
-
-        // Check if there's a third ':' for commit_hash
-        if let Some(third_last_colon) = remaining.rfind(':') {
-            let potential_hash = &remaining[..third_last_colon];
-
-            // Check if it looks like a commit hash (40 hex chars)
-            if potential_hash.len() == 40 && potential_hash.chars().all(|c| c.is_ascii_hexdigit()) {
-                let file_path = &remaining[third_last_colon + 1..];
-
-                if !file_path.is_empty() && !rule_id.is_empty() {
-                    return PatternType::Fingerprint {
-                        commit_hash: Some(potential_hash.to_string()),
-                        file_path: file_path.to_string(),
-                        rule_id: rule_id.to_string(),
-                        line_number,
-                    };
-                }
-            }
-        }
-
-        // No commit hash, treat remaining as file_path
-        let file_path = remaining;
-
-        // Validate that we have non-empty components
-        if file_path.is_empty() || rule_id.is_empty() {
-            return PatternType::Invalid;
+        match trimmed.parse::<Fingerprint>() {
+            Ok(fingerprint) => PatternType::Fingerprint(fingerprint),
+            Err(_) => PatternType::Invalid,
         }
+    }
 
-        PatternType::Fingerprint {
-            commit_hash: None,
-            file_path: file_path.to_string(),
-            rule_id: rule_id.to_string(),
-            line_number,
+    /// Fingerprint of this entry, if it is one
+    pub fn fingerprint(&self) -> Option<&Fingerprint> {
+        match self {
+            PatternType::Fingerprint(fingerprint) => Some(fingerprint),
+            _ => None,
         }
     }
 }
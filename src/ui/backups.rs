@@ -0,0 +1,133 @@
+/// Backup browser widget
+use crate::app::{BackupState, ViewState};
+use crate::ui::conflict::ConflictWidget;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+pub struct BackupsWidget;
+
+impl BackupsWidget {
+    /// Render the backup list above a diff of the highlighted backup
+    pub fn render(
+        f: &mut Frame,
+        view_state: &ViewState,
+        backup_state: &BackupState,
+        read_only: bool,
+        message: Option<&str>,
+    ) {
+        let area = f.size();
+        let list_height = (backup_state.entries.len() as u16 + 2)
+            .min(area.height / 3)
+            .max(3);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(list_height),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        // Keep the cursor inside the visible window (minus borders)
+        let height = chunks[0].height.saturating_sub(2) as usize;
+        let start = if height > 0 && backup_state.cursor >= height {
+            backup_state.cursor + 1 - height
+        } else {
+            0
+        };
+
+        let mut lines = Vec::new();
+        for (idx, entry) in backup_state
+            .entries
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(height)
+        {
+            let base_style = if idx == backup_state.cursor {
+                Style::default().bg(Color::Rgb(40, 40, 50))
+            } else {
+                Style::default()
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{}  ", entry.timestamp()),
+                    base_style.fg(Color::Yellow),
+                ),
+                Span::styled(entry.operation.clone(), base_style.fg(Color::White)),
+                Span::styled(format!("  {}", entry.id), base_style.fg(Color::DarkGray)),
+            ]));
+        }
+
+        let file_name = view_state
+            .file_context
+            .file_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or(".gitleaksignore");
+        let title = format!(
+            " Backups of {} ({}), taken before each change ",
+            file_name,
+            backup_state.entries.len()
+        );
+        let paragraph =
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(paragraph, chunks[0]);
+
+        // Diff from the current file to the highlighted backup
+        let mut lines = vec![Line::from(vec![
+            Span::styled(
+                "- only in the current file",
+                Style::default().fg(Color::Red),
+            ),
+            Span::raw("   "),
+            Span::styled("+ only in the backup", Style::default().fg(Color::Green)),
+        ])];
+        if let Some(ref error) = backup_state.error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+        } else if backup_state.hunks.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "Identical to the current file",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        let height = chunks[1].height.saturating_sub(3) as usize;
+        lines.extend(
+            backup_state
+                .hunks
+                .iter()
+                .skip(backup_state.scroll)
+                .take(height)
+                .map(ConflictWidget::diff_line),
+        );
+
+        let title = match backup_state.entries.get(backup_state.cursor) {
+            Some(entry) => format!(" Restoring {} would change ", entry.id),
+            None => " Diff ".to_string(),
+        };
+        let paragraph =
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(paragraph, chunks[1]);
+
+        let status = if let Some(msg) = message {
+            format!(" BACKUPS | {} ", msg)
+        } else if read_only {
+            " BACKUPS | Read-only | j/k:move Ctrl+d/u:scroll diff q:back ".to_string()
+        } else {
+            " BACKUPS | j/k:move Ctrl+d/u:scroll diff Enter:restore q:back ".to_string()
+        };
+        let paragraph =
+            Paragraph::new(status).style(Style::default().bg(Color::Blue).fg(Color::White));
+        f.render_widget(paragraph, chunks[2]);
+    }
+}
@@ -0,0 +1,144 @@
+/// Write conflict view (T043)
+use crate::app::{ConflictState, ViewState};
+use crate::ui::viewer::ViewerWidget;
+use gli_editor::core::diff::{DiffKind, DiffLine};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+pub struct ConflictWidget;
+
+impl ConflictWidget {
+    /// Render the conflict modal over the viewer
+    pub fn render(f: &mut Frame, view_state: &ViewState, conflict_state: &ConflictState) {
+        ViewerWidget::render(f, view_state, Some("File changed on disk"));
+
+        let area = Self::centered(f.size(), 80, 70);
+        f.render_widget(Clear, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Min(1),
+                Constraint::Length(2),
+            ])
+            .margin(1)
+            .split(area);
+
+        let file_name = view_state
+            .file_context
+            .file_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or(".gitleaksignore");
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} changed on disk ", file_name))
+            .style(Style::default().fg(Color::Yellow));
+        f.render_widget(block, area);
+
+        // Explanation
+        let header = vec![
+            Line::from(format!(
+                "Another process modified the file while saving: {}",
+                conflict_state.change.description
+            )),
+            Line::from(vec![
+                Span::styled(
+                    "- only on disk (lost on overwrite)",
+                    Style::default().fg(Color::Red),
+                ),
+                Span::raw("   "),
+                Span::styled("+ only in your version", Style::default().fg(Color::Green)),
+            ]),
+        ];
+        f.render_widget(
+            Paragraph::new(header).style(Style::default().fg(Color::White)),
+            chunks[0],
+        );
+
+        // Diff hunks
+        let height = chunks[1].height as usize;
+        let lines: Vec<Line> = conflict_state
+            .hunks
+            .iter()
+            .skip(conflict_state.scroll)
+            .take(height)
+            .map(Self::diff_line)
+            .collect();
+        f.render_widget(Paragraph::new(lines), chunks[1]);
+
+        // Choices
+        let mut footer = vec![Line::from(vec![
+            Span::styled(" r ", Style::default().fg(Color::Black).bg(Color::Yellow)),
+            Span::raw(" reload (discard your change)  "),
+            Span::styled(" o ", Style::default().fg(Color::Black).bg(Color::Yellow)),
+            Span::raw(" overwrite  "),
+            Span::styled(" m ", Style::default().fg(Color::Black).bg(Color::Yellow)),
+            Span::raw(" merge onto the file on disk  "),
+            Span::styled(" Esc ", Style::default().fg(Color::Black).bg(Color::Yellow)),
+            Span::raw(" cancel  "),
+            Span::styled("j/k:scroll", Style::default().fg(Color::DarkGray)),
+        ])];
+        if let Some(ref error) = conflict_state.error {
+            footer.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+        }
+        f.render_widget(
+            Paragraph::new(footer).style(Style::default().fg(Color::White)),
+            chunks[2],
+        );
+    }
+
+    /// A diff line with its line number and `-`/`+` marker; None renders as a gap
+    pub fn diff_line(hunk: &Option<DiffLine>) -> Line<'static> {
+        match hunk {
+            None => Line::from(Span::styled(
+                "      ⋯",
+                Style::default().fg(Color::DarkGray),
+            )),
+            Some(line) => {
+                let (marker, line_number, style) = match line.kind {
+                    DiffKind::Equal => (" ", line.new_line, Style::default().fg(Color::Gray)),
+                    DiffKind::Removed => ("-", line.old_line, Style::default().fg(Color::Red)),
+                    DiffKind::Added => ("+", line.new_line, Style::default().fg(Color::Green)),
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>5} ", line_number.unwrap_or(0)),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(format!("{} {}", marker, line.content), style),
+                ])
+            }
+        }
+    }
+
+    /// Rectangle of the given percentage size centered in `area`
+    fn centered(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+        let vertical = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage((100 - percent_y) / 2),
+                Constraint::Percentage(percent_y),
+                Constraint::Percentage((100 - percent_y) / 2),
+            ])
+            .split(area);
+
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage((100 - percent_x) / 2),
+                Constraint::Percentage(percent_x),
+                Constraint::Percentage((100 - percent_x) / 2),
+            ])
+            .split(vertical[1])[1]
+    }
+}
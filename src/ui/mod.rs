@@ -1,4 +1,9 @@
 /// UI components for gli-editor (Ratatui-based TUI)
 ///
 /// This module contains the terminal user interface implementation.
+pub mod backups;
+pub mod clipboard;
+pub mod conflict;
+pub mod report;
+pub mod theme;
 pub mod viewer;
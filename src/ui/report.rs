@@ -0,0 +1,105 @@
+/// Report picker widget
+use crate::app::ReportState;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+pub struct ReportWidget;
+
+impl ReportWidget {
+    /// Render the gitleaks report picker
+    pub fn render(
+        f: &mut Frame,
+        report_state: &ReportState,
+        read_only: bool,
+        message: Option<&str>,
+    ) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(f.size());
+
+        // Keep the cursor inside the visible window (minus borders)
+        let height = chunks[0].height.saturating_sub(2) as usize;
+        let start = if height > 0 && report_state.cursor >= height {
+            report_state.cursor + 1 - height
+        } else {
+            0
+        };
+
+        let mut lines = Vec::new();
+
+        for (idx, finding) in report_state.findings.iter().enumerate().skip(start).take(height) {
+            let is_current = idx == report_state.cursor;
+            let is_ignored = report_state.ignored[idx];
+
+            let checkbox = if is_ignored {
+                "[=] "
+            } else if report_state.selected[idx] {
+                "[x] "
+            } else {
+                "[ ] "
+            };
+
+            let base_style = if is_current {
+                Style::default().bg(Color::Rgb(40, 40, 50))
+            } else {
+                Style::default()
+            };
+
+            let spans = if is_ignored {
+                // Already present in the ignore file
+                let dimmed = base_style.fg(Color::DarkGray);
+                vec![
+                    Span::styled(checkbox, dimmed),
+                    Span::styled(finding.fingerprint.clone(), dimmed),
+                ]
+            } else {
+                let checkbox_style = if is_current {
+                    base_style.fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    base_style.fg(Color::Yellow)
+                };
+                vec![
+                    Span::styled(checkbox, checkbox_style),
+                    Span::styled(format!("{} ", finding.rule_id), base_style.fg(Color::Magenta)),
+                    Span::styled(
+                        format!("{}:{} ", finding.file, finding.start_line),
+                        base_style.fg(Color::Cyan),
+                    ),
+                    Span::styled(
+                        finding.commit.get(..7).map(|c| format!("{} ", c)).unwrap_or_default(),
+                        base_style.fg(Color::Yellow),
+                    ),
+                    Span::styled(finding.fingerprint.clone(), base_style.fg(Color::Gray)),
+                ]
+            };
+
+            lines.push(Line::from(spans));
+        }
+
+        let title = format!(
+            " Report findings ({} selected / {} total) ",
+            report_state.selected_count(),
+            report_state.findings.len()
+        );
+        let paragraph =
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(paragraph, chunks[0]);
+
+        let status = if let Some(msg) = message {
+            format!(" REPORT | {} ", msg)
+        } else if read_only {
+            " REPORT | Read-only | j/k:move q:back ".to_string()
+        } else {
+            " REPORT | j/k:move Space:select a:all Enter:append q:back ".to_string()
+        };
+        let paragraph =
+            Paragraph::new(status).style(Style::default().bg(Color::Blue).fg(Color::White));
+        f.render_widget(paragraph, chunks[1]);
+    }
+}
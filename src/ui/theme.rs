@@ -0,0 +1,63 @@
+/// Colors of the file view (the `[colors]` config section)
+use gli_editor::core::config::ColorConfig;
+use gli_editor::error::{GliError, Result};
+use ratatui::style::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub commit_hash: Color,
+    pub file_path: Color,
+    pub rule_id: Color,
+    pub line_number: Color,
+    pub comment: Color,
+    pub invalid: Color,
+    pub stale: Color,
+    /// Background of the current line
+    pub current_line: Color,
+    /// Background of the visual-line selection
+    pub selection: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            commit_hash: Color::Yellow,
+            file_path: Color::Cyan,
+            rule_id: Color::Magenta,
+            line_number: Color::Green,
+            comment: Color::DarkGray,
+            invalid: Color::Red,
+            stale: Color::Rgb(150, 90, 90),
+            current_line: Color::Rgb(40, 40, 50),
+            selection: Color::Rgb(60, 50, 90),
+        }
+    }
+}
+
+impl Theme {
+    /// Default colors overridden by the configured ones
+    pub fn from_config(colors: &ColorConfig) -> Result<Self> {
+        let defaults = Self::default();
+        let color = |key: &str, value: &Option<String>, default: Color| match value {
+            Some(name) => name.parse::<Color>().map_err(|_| {
+                GliError::InvalidConfig(format!(
+                    "colors.{}: unknown color {:?} (use a name like \"lightblue\", \"#rrggbb\" or 0-255)",
+                    key, name
+                ))
+            }),
+            None => Ok(default),
+        };
+
+        Ok(Self {
+            commit_hash: color("commit_hash", &colors.commit_hash, defaults.commit_hash)?,
+            file_path: color("file_path", &colors.file_path, defaults.file_path)?,
+            rule_id: color("rule_id", &colors.rule_id, defaults.rule_id)?,
+            line_number: color("line_number", &colors.line_number, defaults.line_number)?,
+            comment: color("comment", &colors.comment, defaults.comment)?,
+            invalid: color("invalid", &colors.invalid, defaults.invalid)?,
+            stale: color("stale", &colors.stale, defaults.stale)?,
+            current_line: color("current_line", &colors.current_line, defaults.current_line)?,
+            selection: color("selection", &colors.selection, defaults.selection)?,
+        })
+    }
+}
@@ -1,6 +1,11 @@
 /// Line viewing widget
-use crate::app::{EditState, PreviewContent, ViewState};
-use crate::models::pattern::PatternType;
+use crate::app::{EditState, FilterState, PreviewContent, SearchState, ViewState};
+use crate::ui::theme::Theme;
+use gli_editor::core::file_reader::Encoding;
+use gli_editor::core::highlight::{Token, TokenKind};
+use gli_editor::core::search::Search;
+use gli_editor::models::fingerprint::Fingerprint;
+use gli_editor::models::pattern::PatternType;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -8,6 +13,8 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
+use std::ops::Range;
+use tui_textarea::TextArea;
 
 pub struct ViewerWidget;
 
@@ -19,27 +26,162 @@ impl ViewerWidget {
             .constraints([Constraint::Min(1), Constraint::Length(1)])
             .split(f.size());
 
+        Self::render_main(f, view_state, false, chunks[0]);
+
+        // Render status line with optional save message (T040)
+        Self::render_status(f, view_state, save_message, chunks[1]);
+    }
+
+    /// Render the viewer with the search prompt in place of the status line
+    pub fn render_search_mode(f: &mut Frame, view_state: &ViewState, search_state: &SearchState) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(f.size());
+
+        Self::render_main(f, view_state, search_state.in_preview, chunks[0]);
+
+        let option_style = |enabled: bool| {
+            if enabled {
+                Style::default().fg(Color::Black).bg(Color::Green)
+            } else {
+                Style::default().fg(Color::Gray)
+            }
+        };
+
+        let mut spans = Self::prompt_spans("/", &search_state.textarea);
+        spans.extend([
+            Span::raw("  "),
+            Span::styled(" Tab:ignore-case ", option_style(search_state.case_insensitive)),
+            Span::raw(" "),
+            Span::styled(" Ctrl+R:regex ", option_style(search_state.regex)),
+        ]);
+
+        if let Some(ref error) = search_state.error {
+            spans.push(Span::styled(format!("  {}", error), Style::default().fg(Color::Red)));
+        }
+
+        f.render_widget(Paragraph::new(Line::from(spans)), chunks[1]);
+    }
+
+    /// Render the viewer with the filter prompt in place of the status line
+    pub fn render_filter_mode(f: &mut Frame, view_state: &ViewState, filter_state: &FilterState) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(f.size());
+
+        Self::render_main(f, view_state, false, chunks[0]);
+
+        let mut spans = Self::prompt_spans("filter: ", &filter_state.textarea);
+        match filter_state.error {
+            Some(ref error) => {
+                spans.push(Span::styled(format!("  {}", error), Style::default().fg(Color::Red)));
+            }
+            None => {
+                spans.push(Span::styled(
+                    "  e.g. rule:aws-* path:src/**/*.go commit:cd52267 (empty clears)",
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+        }
+
+        f.render_widget(Paragraph::new(Line::from(spans)), chunks[1]);
+    }
+
+    /// Render the viewer in visual-line mode
+    pub fn render_visual_mode(f: &mut Frame, view_state: &ViewState) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(f.size());
+
+        Self::render_main(f, view_state, false, chunks[0]);
+
+        let selected = view_state
+            .selected_rows()
+            .map(|(start, end)| end - start + 1)
+            .unwrap_or(0);
+        let status = format!(
+            " VISUAL | {} line(s) selected | j/k:extend d:delete c:toggle comment y:yank Esc:cancel ",
+            selected
+        );
+        let paragraph =
+            Paragraph::new(status).style(Style::default().bg(Color::Magenta).fg(Color::Black));
+        f.render_widget(paragraph, chunks[1]);
+    }
+
+    /// Render the viewer with the preview pane focused
+    pub fn render_preview_mode(f: &mut Frame, view_state: &ViewState, save_message: Option<&str>) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(f.size());
+
+        Self::render_main(f, view_state, true, chunks[0]);
+
+        let status = match save_message {
+            Some(msg) => format!(" PREVIEW | {} ", msg),
+            None => format!(
+                " PREVIEW | Line {}/{} | j/k:scroll /:search t:target +/-:context {} </>:resize Tab:back ",
+                view_state.preview_focus_line(),
+                view_state.preview_content.as_ref().map_or(0, |p| p.source.lines.len()),
+                view_state.preview_context
+            ),
+        };
+        let paragraph =
+            Paragraph::new(status).style(Style::default().bg(Color::Cyan).fg(Color::Black));
+        f.render_widget(paragraph, chunks[1]);
+    }
+
+    /// Spans for a single-line prompt with a block cursor
+    fn prompt_spans(prefix: &'static str, textarea: &TextArea) -> Vec<Span<'static>> {
+        let mut spans =
+            vec![Span::styled(prefix, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))];
+        spans.extend(Self::cursor_spans(textarea));
+        spans
+    }
+
+    /// Spans of the textarea content with a block cursor at the cursor column
+    ///
+    /// The textarea reports the column in characters, so it is converted to a byte
+    /// offset before splitting (lines may contain multi-byte replacement characters).
+    fn cursor_spans(textarea: &TextArea) -> Vec<Span<'static>> {
+        let input = textarea.lines().join("");
+        let cursor_col = textarea.cursor().1;
+        let split_at = input.char_indices().nth(cursor_col).map_or(input.len(), |(idx, _)| idx);
+
+        vec![
+            Span::raw(input[..split_at].to_string()),
+            Span::styled("█", Style::default().fg(Color::White)),
+            Span::raw(input[split_at..].to_string()),
+        ]
+    }
+
+    /// Render the gitleaksignore content and, if enabled, the preview pane
+    fn render_main(f: &mut Frame, view_state: &ViewState, preview_focused: bool, area: Rect) {
         // Split content area into left (gitleaksignore) and right (preview) if preview is enabled
-        if view_state.preview_enabled && view_state.preview_content.is_some() {
+        if view_state.preview_shown() {
+            let preview_width = view_state.preview_width;
             let content_chunks = Layout::default()
                 .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                .split(chunks[0]);
+                .constraints([
+                    Constraint::Percentage(100 - preview_width),
+                    Constraint::Percentage(preview_width),
+                ])
+                .split(area);
 
             // Render gitleaksignore content on left
             Self::render_content(f, view_state, content_chunks[0]);
 
             // Render preview on right
             if let Some(ref preview) = view_state.preview_content {
-                Self::render_preview(f, preview, content_chunks[1]);
+                Self::render_preview(f, view_state, preview, preview_focused, content_chunks[1]);
             }
         } else {
             // Render full-width content
-            Self::render_content(f, view_state, chunks[0]);
+            Self::render_content(f, view_state, area);
         }
-
-        // Render status line with optional save message (T040)
-        Self::render_status(f, view_state, save_message, chunks[1]);
     }
 
     /// Render the viewer widget in edit mode (T031)
@@ -57,18 +199,15 @@ impl ViewerWidget {
         Self::render_content(f, view_state, chunks[0]);
 
         // Render edit area - create a bordered block for the textarea
-        let content = edit_state.textarea.lines().join("");
-        let cursor_pos = edit_state.textarea.cursor();
-
-        let edit_line = Line::from(vec![
-            Span::raw(&content[..cursor_pos.1]),
-            Span::styled("█", Style::default().fg(Color::White)),
-            Span::raw(&content[cursor_pos.1..]),
-        ]);
+        let edit_line = Line::from(Self::cursor_spans(&edit_state.textarea));
 
         let edit_block = Block::default()
             .borders(Borders::ALL)
-            .title(format!(" Editing line {} ", edit_state.original_line))
+            .title(if edit_state.inserted {
+                format!(" New line {} ", edit_state.original_line)
+            } else {
+                format!(" Editing line {} ", edit_state.original_line)
+            })
             .style(Style::default().fg(Color::Yellow));
 
         let edit_paragraph = Paragraph::new(vec![edit_line]).block(edit_block);
@@ -83,13 +222,15 @@ impl ViewerWidget {
 
     fn render_content(f: &mut Frame, view_state: &ViewState, area: Rect) {
         let mut lines = Vec::new();
+        let selection = view_state.selected_rows();
 
-        for line in &view_state.visible_range.entries {
-            let is_current = line.line_number == view_state.current_line;
+        for (row, line_number, line) in view_state.visible_lines() {
+            let is_current = line_number == view_state.current_line;
+            let is_selected = selection.is_some_and(|(start, end)| row >= start && row <= end);
 
             // Add cursor indicator for current line
             let cursor_indicator = if is_current { ">" } else { " " };
-            let line_number_str = format!("{}{:>4} ", cursor_indicator, line.line_number);
+            let line_number_str = format!("{}{:>4}", cursor_indicator, line_number);
 
             let line_number_style = if is_current {
                 Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
@@ -97,37 +238,98 @@ impl ViewerWidget {
                 Style::default().fg(Color::DarkGray)
             };
 
-            let mut spans = vec![Span::styled(line_number_str, line_number_style)];
+            // Gutter marker for undecodable lines, then for duplicate fingerprints: red on
+            // later (removable) occurrences, yellow on the first occurrence
+            let gutter_marker = match view_state.file_context.duplicates().first_occurrence(line_number) {
+                _ if line.is_damaged() => Span::styled(
+                    "! ",
+                    Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Some(first) if first != line_number => {
+                    Span::styled("≡ ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+                }
+                Some(_) => Span::styled("≡ ", Style::default().fg(Color::Yellow)),
+                None => Span::raw("  "),
+            };
+
+            let mut spans = vec![Span::styled(line_number_str, line_number_style), gutter_marker];
+
+            let is_stale = view_state
+                .stale_report
+                .as_ref()
+                .is_some_and(|report| report.is_stale(line.content()));
 
             // Add syntax-highlighted content with background highlight for current line
-            let content_spans = Self::highlight_line(&line.content, &line.pattern_type, is_current);
-            spans.extend(content_spans);
+            let content_spans = Self::highlight_line(
+                line.content(),
+                line.pattern_type(),
+                is_current,
+                is_selected,
+                is_stale,
+                &view_state.theme,
+            );
+            match view_state.search {
+                Some(ref search) => spans.extend(Self::highlight_matches(content_spans, search)),
+                None => spans.extend(content_spans),
+            }
 
             lines.push(Line::from(spans));
         }
 
+        let file_name = view_state
+            .file_context
+            .file_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or(".gitleaksignore");
+        let encoding = match view_state.file_context.encoding {
+            Encoding::Utf8 => String::new(),
+            encoding => format!(" [{}]", encoding.name()),
+        };
+        let title = match view_state.filter {
+            Some(ref filter) => {
+                format!(" {}{} [filter: {}] ", file_name, encoding, filter.expression)
+            }
+            None => format!(" {}{} ", file_name, encoding),
+        };
+
         let paragraph =
-            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(
-                        " {} ",
-                        view_state
-                            .file_context
-                            .file_path
-                            .file_name()
-                            .and_then(|s| s.to_str())
-                            .unwrap_or(".gitleaksignore")
-                    )));
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
 
         f.render_widget(paragraph, area);
     }
 
-    fn render_preview(f: &mut Frame, preview: &PreviewContent, area: Rect) {
+    fn render_preview(
+        f: &mut Frame,
+        view_state: &ViewState,
+        preview: &PreviewContent,
+        focused: bool,
+        area: Rect,
+    ) {
         let mut lines = Vec::new();
 
-        for (idx, line_content) in preview.lines.iter().enumerate() {
-            let line_num = preview.start_line + idx;
+        // Placeholder while the source is loaded in the background
+        if preview.loading {
+            lines.push(Line::from(Span::styled(
+                "loading…",
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            )));
+        }
+
+        // Why the source could not be loaded (missing file or commit)
+        if let Some(ref error) = preview.error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )));
+        }
+
+        for (line_num, line_content, tokens) in preview.visible_lines(view_state.preview_context) {
             let is_target = line_num == preview.target_line;
 
-            let line_number_str = format!("{:>4} ", line_num);
+            // Mark the line the pane is scrolled to while it has focus
+            let focus_marker = if focused && line_num == preview.focus_line { ">" } else { " " };
+            let line_number_str = format!("{}{:>4} ", focus_marker, line_num);
             let line_number_style = if is_target {
                 Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
             } else {
@@ -140,38 +342,133 @@ impl ViewerWidget {
                 Style::default()
             };
 
-            // Expand tabs to spaces (tab width = 4)
-            let expanded_content = line_content.replace('\t', "    ");
-
             let mut spans = vec![Span::styled(line_number_str, line_number_style)];
-            spans.push(Span::styled(expanded_content, content_style));
+            let underlined = preview.line_match_ranges(line_num);
+            let masked = if view_state.secret_revealed {
+                Vec::new()
+            } else {
+                preview.line_mask_ranges(line_num)
+            };
+            let content_spans =
+                Self::source_spans(line_content, tokens, &underlined, &masked, content_style);
+            match view_state.preview_search {
+                Some(ref search) => spans.extend(Self::highlight_matches(content_spans, search)),
+                None => spans.extend(content_spans),
+            }
 
             lines.push(Line::from(spans));
         }
 
+        // Yellow border while the pane has keyboard focus
+        let border_color = if focused { Color::Yellow } else { Color::Cyan };
+        let mut title = vec![Span::raw(format!(
+            " Preview: {} (line {}) ",
+            preview.file_path, preview.target_line
+        ))];
+        if view_state.secret_revealed && !preview.mask_ranges.is_empty() {
+            title.push(Span::styled(
+                "[secret revealed] ",
+                Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD),
+            ));
+        }
         let block = Block::default()
             .borders(Borders::ALL)
-            .title(format!(" Preview: {} (line {}) ", preview.file_path, preview.target_line))
-            .style(Style::default().fg(Color::Cyan));
+            .title(Line::from(title))
+            .title_style(Style::default().fg(Color::Cyan))
+            .border_style(Style::default().fg(border_color));
 
         let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
 
         f.render_widget(paragraph, area);
     }
 
+    /// Spans of a source line styled by its syntax tokens, with the reported secret
+    /// underlined, masked ranges replaced by `•••` and tabs expanded to 4 spaces
+    fn source_spans(
+        content: &str,
+        tokens: &[Token],
+        underlined: &[Range<usize>],
+        masked: &[Range<usize>],
+        base: Style,
+    ) -> Vec<Span<'static>> {
+        let underline_style =
+            Style::default().add_modifier(Modifier::UNDERLINED | Modifier::BOLD);
+        let inside = |ranges: &[Range<usize>], start: usize, end: usize| {
+            ranges.iter().find(|range| range.start <= start && end <= range.end).cloned()
+        };
+
+        // Split the line at every token, underline and mask boundary
+        let mut boundaries: Vec<usize> = tokens
+            .iter()
+            .flat_map(|token| [token.start, token.end])
+            .chain(underlined.iter().chain(masked).flat_map(|range| [range.start, range.end]))
+            .chain([0, content.len()])
+            .filter(|&pos| content.is_char_boundary(pos))
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        boundaries
+            .windows(2)
+            .filter_map(|pair| {
+                let (start, end) = (pair[0], pair[1]);
+                let mut style = base;
+                if let Some(token) = tokens.iter().find(|t| t.start <= start && end <= t.end) {
+                    style = style.patch(Self::token_style(token.kind));
+                }
+                if inside(underlined, start, end).is_some() {
+                    style = style.patch(underline_style);
+                }
+                let text = match inside(masked, start, end) {
+                    // One mask per range, hiding the length of the secret
+                    Some(range) if range.start == start => "•••".to_string(),
+                    Some(_) => return None,
+                    None => content[start..end].replace('\t', "    "),
+                };
+                Some(Span::styled(text, style))
+            })
+            .collect()
+    }
+
+    /// Color of a syntax token in the preview
+    fn token_style(kind: TokenKind) -> Style {
+        match kind {
+            TokenKind::Comment => Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            TokenKind::String => Style::default().fg(Color::Green),
+            TokenKind::Keyword => Style::default().fg(Color::Magenta),
+            TokenKind::Number => Style::default().fg(Color::LightBlue),
+            TokenKind::Key => Style::default().fg(Color::Cyan),
+        }
+    }
+
     fn render_status(
         f: &mut Frame,
         view_state: &ViewState,
         save_message: Option<&str>,
         area: Rect,
     ) {
-        let preview_status = if view_state.preview_enabled { "p:toggle" } else { "p:enable" };
+        let preview_status = if !view_state.preview_enabled {
+            "p:enable"
+        } else if view_state.preview_shown() {
+            "p:toggle Tab:preview"
+        } else {
+            "p:toggle"
+        };
 
         let status = if let Some(msg) = save_message {
             format!(" VIEW | {} ", msg)
+        } else if view_state.filter.is_some() {
+            format!(
+                " VIEW | Line {}/{} (match {}/{}) | j/k:scroll /:search f:filter F:clear {} i:edit q:quit ",
+                view_state.current_line,
+                view_state.file_context.total_lines,
+                view_state.current_row(),
+                view_state.total_rows(),
+                preview_status
+            )
         } else {
             format!(
-                " VIEW | Line {}/{} (showing {}-{}) | j/k:scroll {} i:edit q:quit ",
+                " VIEW | Line {}/{} (showing {}-{}) | j/k:scroll /:search f:filter {} i:edit q:quit ",
                 view_state.current_line,
                 view_state.file_context.total_lines,
                 view_state.visible_range.start_line,
@@ -186,29 +483,91 @@ impl ViewerWidget {
         f.render_widget(paragraph, area);
     }
 
+    /// Overlay search match highlighting onto already styled spans
+    fn highlight_matches(spans: Vec<Span<'static>>, search: &Search) -> Vec<Span<'static>> {
+        let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        let ranges = search.find_ranges(&text);
+        if ranges.is_empty() {
+            return spans;
+        }
+
+        let match_style = Style::default().bg(Color::Yellow).fg(Color::Black);
+        let mut result = Vec::new();
+        let mut offset = 0;
+
+        for span in spans {
+            let span_start = offset;
+            let span_end = offset + span.content.len();
+            offset = span_end;
+
+            // Split the span at every match boundary that falls inside it
+            let mut cursor = span_start;
+            for &(match_start, match_end) in &ranges {
+                if match_end <= cursor || match_start >= span_end {
+                    continue;
+                }
+                let start = match_start.max(cursor);
+                let end = match_end.min(span_end);
+                if start > cursor {
+                    result.push(Span::styled(text[cursor..start].to_string(), span.style));
+                }
+                result.push(Span::styled(
+                    text[start..end].to_string(),
+                    span.style.patch(match_style),
+                ));
+                cursor = end;
+            }
+            if cursor < span_end {
+                result.push(Span::styled(text[cursor..span_end].to_string(), span.style));
+            }
+        }
+
+        result
+    }
+
     /// Apply syntax highlighting to a line based on its pattern type
-    fn highlight_line(content: &str, pattern_type: &PatternType, is_current: bool) -> Vec<Span<'static>> {
-        let base_style = if is_current {
-            Style::default().bg(Color::Rgb(40, 40, 50))
+    ///
+    /// Stale fingerprints (file or commit no longer exists) are dimmed and crossed out.
+    fn highlight_line(
+        content: &str,
+        pattern_type: &PatternType,
+        is_current: bool,
+        is_selected: bool,
+        is_stale: bool,
+        theme: &Theme,
+    ) -> Vec<Span<'static>> {
+        let base_style = if is_selected {
+            Style::default().bg(theme.selection)
+        } else if is_current {
+            Style::default().bg(theme.current_line)
         } else {
             Style::default()
         };
 
+        if is_stale {
+            return vec![Span::styled(
+                content.to_string(),
+                base_style
+                    .fg(theme.stale)
+                    .add_modifier(Modifier::CROSSED_OUT),
+            )];
+        }
+
         match pattern_type {
             PatternType::Comment => {
                 vec![Span::styled(
                     content.to_string(),
                     base_style
-                        .fg(Color::DarkGray)
+                        .fg(theme.comment)
                         .add_modifier(Modifier::ITALIC),
                 )]
             }
-            PatternType::Fingerprint {
+            PatternType::Fingerprint(Fingerprint {
                 commit_hash,
                 file_path,
                 rule_id,
                 line_number,
-            } => {
+            }) => {
                 let mut spans = Vec::new();
 
                 // Add commit hash if present
@@ -216,15 +575,15 @@ impl ViewerWidget {
                     spans.push(Span::styled(
                         format!("{}:", hash),
                         base_style
-                            .fg(Color::Yellow)
+                            .fg(theme.commit_hash)
                             .add_modifier(Modifier::BOLD),
                     ));
                 }
 
                 // Add file path, rule ID, and line number
-                spans.push(Span::styled(format!("{}:", file_path), base_style.fg(Color::Cyan)));
-                spans.push(Span::styled(format!("{}:", rule_id), base_style.fg(Color::Magenta)));
-                spans.push(Span::styled(line_number.to_string(), base_style.fg(Color::Green)));
+                spans.push(Span::styled(format!("{}:", file_path), base_style.fg(theme.file_path)));
+                spans.push(Span::styled(format!("{}:", rule_id), base_style.fg(theme.rule_id)));
+                spans.push(Span::styled(line_number.to_string(), base_style.fg(theme.line_number)));
 
                 spans
             }
@@ -235,10 +594,82 @@ impl ViewerWidget {
                 vec![Span::styled(
                     content.to_string(),
                     base_style
-                        .fg(Color::Red)
+                        .fg(theme.invalid)
                         .add_modifier(Modifier::UNDERLINED),
                 )]
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{buffer::Buffer, widgets::Widget};
+    use tui_textarea::CursorMove;
+
+    #[test]
+    fn edit_line_with_replacement_char_renders_cursor_at_end() {
+        let mut textarea = TextArea::new(vec!["key=\u{FFFD}\u{FFFD}".to_string()]);
+        textarea.move_cursor(CursorMove::End);
+
+        let line = Line::from(ViewerWidget::cursor_spans(&textarea));
+        let area = Rect::new(0, 0, 8, 1);
+        let mut buffer = Buffer::empty(area);
+        Paragraph::new(line).render(area, &mut buffer);
+
+        let rendered: String = (0..area.width).map(|x| buffer.get(x, 0).symbol()).collect();
+        assert_eq!(rendered, "key=\u{FFFD}\u{FFFD}█ ");
+    }
+
+    #[test]
+    fn cursor_splits_before_replacement_char() {
+        let mut textarea = TextArea::new(vec!["a\u{FFFD}b".to_string()]);
+        textarea.move_cursor(CursorMove::Head);
+        textarea.move_cursor(CursorMove::Forward);
+
+        let spans = ViewerWidget::cursor_spans(&textarea);
+        assert_eq!(spans[0].content, "a");
+        assert_eq!(spans[2].content, "\u{FFFD}b");
+    }
+
+    #[test]
+    fn underline_after_non_ascii_text_covers_the_secret() {
+        let content = "clé = \"ä\" token=s3cr3t";
+        let start = content.find("s3cr3t").unwrap();
+        let secret = start..start + 6;
+        let spans = ViewerWidget::source_spans(
+            content,
+            &[],
+            std::slice::from_ref(&secret),
+            &[],
+            Style::default(),
+        );
+
+        let underlined: Vec<&str> = spans
+            .iter()
+            .filter(|span| span.style.add_modifier.contains(Modifier::UNDERLINED))
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(underlined, ["s3cr3t"]);
+        let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, content);
+    }
+
+    #[test]
+    fn mask_after_non_ascii_text_hides_only_the_secret() {
+        let content = "☃ key=s3cr3t";
+        let start = content.find("s3cr3t").unwrap();
+        let secret = start..start + 6;
+        let spans = ViewerWidget::source_spans(
+            content,
+            &[],
+            &[],
+            std::slice::from_ref(&secret),
+            Style::default(),
+        );
+
+        let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, "☃ key=•••");
+    }
+}